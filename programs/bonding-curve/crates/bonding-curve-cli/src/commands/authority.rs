@@ -0,0 +1,39 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::{ bail, Result };
+use bonding_curve_sdk::instructions;
+use clap::Args;
+use serde_json::{ json, Value };
+use solana_signer::Signer;
+
+use super::{ merge, Context };
+
+#[derive(Args, Debug)]
+pub struct ProposeAuthorityArgs {
+    /// Key that takes over once it runs `accept-authority`
+    pub new_authority: Pubkey,
+}
+
+pub fn propose(ctx: &Context, args: ProposeAuthorityArgs) -> Result<Value> {
+    let authority = ctx.payer()?.pubkey();
+    let instruction = instructions::propose_authority(&authority, &args.new_authority);
+    let outcome = ctx.execute(&[instruction], &[])?;
+    Ok(merge(json!({ "pending_authority": args.new_authority.to_string() }), outcome))
+}
+
+pub fn accept(ctx: &Context) -> Result<Value> {
+    let new_authority = ctx.payer()?.pubkey();
+    if ctx.global()?.pending_authority != Some(new_authority) {
+        bail!("{} is not the pending global authority", new_authority);
+    }
+    let outcome = ctx.execute(&[instructions::accept_authority(&new_authority)], &[])?;
+    Ok(merge(json!({ "global_authority": new_authority.to_string() }), outcome))
+}
+
+pub fn cancel(ctx: &Context) -> Result<Value> {
+    let Some(pending_authority) = ctx.global()?.pending_authority else {
+        bail!("no global authority transfer is pending");
+    };
+    let authority = ctx.payer()?.pubkey();
+    let outcome = ctx.execute(&[instructions::cancel_authority_transfer(&authority)], &[])?;
+    Ok(merge(json!({ "cancelled_authority": pending_authority.to_string() }), outcome))
+}
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{ anyhow, Result };
+use bonding_curve_sdk::{
+    bonding_curve::{ CreateBondingCurveParams, FeeSchedule },
+    instructions,
+    pda,
+};
+use clap::Args;
+use serde_json::{ json, Value };
+use solana_keypair::{ read_keypair_file, Keypair };
+use solana_signer::Signer;
+
+use super::{ merge, parse_fee_schedule, Context };
+
+#[derive(Args, Debug)]
+pub struct CreateArgs {
+    #[arg(long)]
+    pub name: String,
+    #[arg(long)]
+    pub symbol: String,
+    #[arg(long)]
+    pub uri: String,
+    /// Lamports to raise before the curve completes
+    #[arg(long)]
+    pub sol_raise_target: u64,
+    /// DAO treasury that receives the migrated SOL and reserved tokens
+    #[arg(long)]
+    pub realm: Pubkey,
+    /// Unix timestamp trading opens at, converted to an estimated slot
+    #[arg(long)]
+    pub start_time: Option<i64>,
+    /// Slot trading opens at, takes precedence over `--start-time`
+    #[arg(long)]
+    pub start_slot: Option<u64>,
+    /// Unix timestamp after which an incomplete curve turns refundable
+    #[arg(long)]
+    pub end_time: Option<i64>,
+    /// Stop the raise exactly at the target
+    #[arg(long)]
+    pub hard_cap: bool,
+    #[arg(long)]
+    pub market_cap_target_lamports: Option<u64>,
+    /// Overrides the global fee schedule, same format as `init-global --fee-schedule`
+    #[arg(long, value_parser = parse_fee_schedule)]
+    pub fee_schedule: Option<FeeSchedule>,
+    /// Id of the launch config to launch from instead of the global settings
+    #[arg(long)]
+    pub launch_config: Option<u64>,
+    /// Keypair of the new mint, a fresh one is generated by default
+    #[arg(long)]
+    pub mint_keypair: Option<PathBuf>,
+}
+
+pub fn run(ctx: &Context, args: CreateArgs) -> Result<Value> {
+    let creator = ctx.payer()?.pubkey();
+    let mint = match &args.mint_keypair {
+        Some(path) => {
+            read_keypair_file(path).map_err(|err| anyhow!("{}: {}", path.display(), err))?
+        }
+        None => Keypair::new(),
+    };
+
+    let params = CreateBondingCurveParams {
+        name: args.name,
+        symbol: args.symbol,
+        uri: args.uri,
+        start_time: args.start_time,
+        start_slot: args.start_slot,
+        sol_raise_target: args.sol_raise_target,
+        realm_pubkey: args.realm,
+        fee_schedule: args.fee_schedule,
+        hard_cap: args.hard_cap,
+        end_time: args.end_time,
+        market_cap_target_lamports: args.market_cap_target_lamports,
+    };
+    let whitelisted = ctx.global()?.whitelist_enabled;
+    let launch_config = args.launch_config.map(pda::launch_config_address);
+    let instruction = instructions::create_bonding_curve(
+        &creator,
+        &mint.pubkey(),
+        params,
+        whitelisted,
+        launch_config
+    );
+
+    let outcome = ctx.execute(&[instruction], &[&mint])?;
+    Ok(
+        merge(
+            json!({
+                "mint": mint.pubkey().to_string(),
+                "bonding_curve": pda::bonding_curve_address(&mint.pubkey()).to_string(),
+                "creator": creator.to_string(),
+            }),
+            outcome
+        )
+    )
+}
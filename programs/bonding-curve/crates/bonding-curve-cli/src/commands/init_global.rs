@@ -0,0 +1,85 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use bonding_curve_sdk::{
+    bonding_curve::{ FeeSchedule, GlobalSettingsInput, ProgramStatus },
+    instructions,
+    pda,
+};
+use clap::{ Args, ValueEnum };
+use serde_json::{ json, Value };
+use solana_signer::Signer;
+
+use super::{ merge, parse_fee_schedule, Context };
+
+/// Settings left out keep the program defaults
+#[derive(Args, Debug)]
+pub struct InitGlobalArgs {
+    #[arg(long)]
+    pub initial_virtual_token_reserves: Option<u64>,
+    #[arg(long)]
+    pub initial_virtual_sol_reserves: Option<u64>,
+    #[arg(long)]
+    pub initial_real_token_reserves: Option<u64>,
+    #[arg(long)]
+    pub token_total_supply: Option<u64>,
+    #[arg(long)]
+    pub mint_decimals: Option<u8>,
+    #[arg(long)]
+    pub migrate_fee_amount: Option<u64>,
+    /// Defaults to the signing keypair
+    #[arg(long)]
+    pub fee_receiver: Option<Pubkey>,
+    #[arg(long, value_enum)]
+    pub status: Option<Status>,
+    #[arg(long)]
+    pub whitelist_enabled: Option<bool>,
+    /// Comma separated `elapsed_slots:fee_bps` breakpoints, e.g. `0:9900,150:9900,250:100`
+    #[arg(long, value_parser = parse_fee_schedule)]
+    pub fee_schedule: Option<FeeSchedule>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Status {
+    Running,
+    SwapOnly,
+    SwapOnlyNoLaunch,
+    Paused,
+}
+
+impl From<Status> for ProgramStatus {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Running => ProgramStatus::Running,
+            Status::SwapOnly => ProgramStatus::SwapOnly,
+            Status::SwapOnlyNoLaunch => ProgramStatus::SwapOnlyNoLaunch,
+            Status::Paused => ProgramStatus::Paused,
+        }
+    }
+}
+
+pub fn run(ctx: &Context, args: InitGlobalArgs) -> Result<Value> {
+    let admin = ctx.payer()?.pubkey();
+    let params = GlobalSettingsInput {
+        initial_virtual_token_reserves: args.initial_virtual_token_reserves,
+        initial_virtual_sol_reserves: args.initial_virtual_sol_reserves,
+        initial_real_token_reserves: args.initial_real_token_reserves,
+        token_total_supply: args.token_total_supply,
+        mint_decimals: args.mint_decimals,
+        migrate_fee_amount: args.migrate_fee_amount,
+        fee_receiver: Some(args.fee_receiver.unwrap_or(admin)),
+        status: args.status.map(ProgramStatus::from),
+        whitelist_enabled: args.whitelist_enabled,
+        fee_schedule: args.fee_schedule,
+    };
+
+    let outcome = ctx.execute(&[instructions::initialize(&admin, params)], &[])?;
+    Ok(
+        merge(
+            json!({
+                "global": pda::global_address().to_string(),
+                "global_authority": admin.to_string(),
+            }),
+            outcome
+        )
+    )
+}
@@ -0,0 +1,139 @@
+use anchor_lang::{ prelude::Pubkey, solana_program::instruction::Instruction };
+use anyhow::{ bail, Context as _, Result };
+use bonding_curve_sdk::{
+    accounts,
+    bonding_curve::{ BondingCurve, FeeBreakpoint, FeeSchedule, Global },
+    pda,
+};
+use serde_json::{ json, Value };
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::rpc::RpcClient;
+
+pub mod authority;
+pub mod create;
+pub mod init_global;
+pub mod roles;
+pub mod status;
+pub mod swap;
+
+/// Swaps and launches with metadata go past the default 200k compute units
+const COMPUTE_UNIT_LIMIT: u32 = 1_000_000;
+
+pub struct Context {
+    pub rpc: RpcClient,
+    /// Only loaded for commands that sign
+    pub payer: Option<Keypair>,
+    pub dry_run: bool,
+}
+
+impl Context {
+    pub fn payer(&self) -> Result<&Keypair> {
+        self.payer.as_ref().context("no keypair loaded")
+    }
+
+    /// Signs `instructions` with the payer and `signers`, then sends them, or only
+    /// simulates them with `--dry-run`
+    pub fn execute(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<Value> {
+        let mut all_instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT)
+        ];
+        all_instructions.extend_from_slice(instructions);
+
+        let payer = self.payer()?;
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+
+        let transaction = Transaction::new_signed_with_payer(
+            &all_instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            self.rpc.get_latest_blockhash()?
+        );
+
+        if self.dry_run {
+            let simulation = self.rpc.simulate_transaction(&transaction)?;
+            if let Some(err) = simulation.err {
+                bail!("simulation failed: {}\n{}", err, simulation.logs.join("\n"));
+            }
+            return Ok(
+                json!({
+                    "dry_run": true,
+                    "units_consumed": simulation.units_consumed,
+                    "logs": simulation.logs,
+                })
+            );
+        }
+
+        let signature = self.rpc.send_and_confirm_transaction(&transaction)?;
+        Ok(json!({ "signature": signature }))
+    }
+
+    pub fn global(&self) -> Result<Global> {
+        let data = self.rpc
+            .get_account_data(&pda::global_address())?
+            .with_context(|| format!("program not initialized on {}", self.rpc.url()))?;
+        Ok(accounts::decode_global(&data)?)
+    }
+
+    pub fn bonding_curve(&self, mint: &Pubkey) -> Result<BondingCurve> {
+        let data = self.rpc
+            .get_account_data(&pda::bonding_curve_address(mint))?
+            .with_context(|| format!("no bonding curve for mint {}", mint))?;
+        Ok(accounts::decode_bonding_curve(&data)?)
+    }
+}
+
+/// Parses `elapsed:fee_bps` pairs separated by commas, e.g. `0:9900,150:9900,250:100`
+pub fn parse_fee_schedule(value: &str) -> Result<FeeSchedule, String> {
+    let breakpoints = value
+        .split(',')
+        .map(|breakpoint| {
+            let (elapsed, fee_bps) = breakpoint
+                .split_once(':')
+                .ok_or_else(|| format!("expected elapsed:fee_bps, got {}", breakpoint))?;
+            Ok(FeeBreakpoint {
+                elapsed: elapsed.trim().parse().map_err(|err| format!("{}: {}", elapsed, err))?,
+                fee_bps: fee_bps.trim().parse().map_err(|err| format!("{}: {}", fee_bps, err))?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(FeeSchedule { breakpoints })
+}
+
+pub fn fee_schedule_json(fee_schedule: &FeeSchedule) -> Value {
+    fee_schedule.breakpoints
+        .iter()
+        .map(|breakpoint| format!("{}:{}", breakpoint.elapsed, breakpoint.fee_bps))
+        .collect::<Vec<_>>()
+        .join(",")
+        .into()
+}
+
+/// Merges the fields of `extra` into `value`, both must be objects
+pub fn merge(mut value: Value, extra: Value) -> Value {
+    if let (Value::Object(fields), Value::Object(extra)) = (&mut value, extra) {
+        fields.extend(extra);
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_schedule_round_trips() {
+        let fee_schedule = parse_fee_schedule("0:9900, 150:9900,250:100").unwrap();
+        assert_eq!(fee_schedule.breakpoints.len(), 3);
+        assert_eq!(fee_schedule.breakpoints[1].elapsed, 150);
+        assert_eq!(fee_schedule.breakpoints[2].fee_bps, 100);
+        assert_eq!(fee_schedule_json(&fee_schedule), "0:9900,150:9900,250:100");
+
+        assert!(parse_fee_schedule("0-9900").is_err());
+        assert!(parse_fee_schedule("0:99000").is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::{ bail, Result };
+use bonding_curve_sdk::{
+    bonding_curve::{ GlobalSettingsInput, ProgramStatus, Roles },
+    instructions,
+    pda,
+};
+use clap::Args;
+use serde_json::{ json, Value };
+use solana_signer::Signer;
+
+use super::{ init_global::Status, merge, Context };
+
+#[derive(Args, Debug)]
+pub struct RolesArgs {
+    pub member: Pubkey,
+    /// Can pause the program
+    #[arg(long)]
+    pub pauser: bool,
+    /// Can change the fee receiver, migration fee and fee schedule
+    #[arg(long)]
+    pub fee_manager: bool,
+    /// Can change reserves, supply, decimals and status, the whitelist and launch configs
+    #[arg(long)]
+    pub config_manager: bool,
+}
+
+impl RolesArgs {
+    fn permissions(&self) -> Result<u8> {
+        let permissions = [
+            (self.pauser, Roles::PAUSER),
+            (self.fee_manager, Roles::FEE_MANAGER),
+            (self.config_manager, Roles::CONFIG_MANAGER),
+        ]
+            .iter()
+            .filter(|(set, _)| *set)
+            .fold(0, |permissions, (_, role)| permissions | role);
+        if permissions == 0 {
+            bail!("pick at least one of --pauser, --fee-manager and --config-manager");
+        }
+        Ok(permissions)
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct SetStatusArgs {
+    #[arg(value_enum)]
+    pub status: Status,
+}
+
+pub fn grant(ctx: &Context, args: RolesArgs) -> Result<Value> {
+    let authority = ctx.payer()?.pubkey();
+    let instruction = instructions::grant_roles(&authority, &args.member, args.permissions()?);
+    let outcome = ctx.execute(&[instruction], &[])?;
+    Ok(merge(json!({ "roles": pda::roles_address(&args.member).to_string() }), outcome))
+}
+
+pub fn revoke(ctx: &Context, args: RolesArgs) -> Result<Value> {
+    let authority = ctx.payer()?.pubkey();
+    let instruction = instructions::revoke_roles(&authority, &args.member, args.permissions()?);
+    let outcome = ctx.execute(&[instruction], &[])?;
+    Ok(merge(json!({ "roles": pda::roles_address(&args.member).to_string() }), outcome))
+}
+
+/// Works for the global authority, for pausers when pausing, and for config managers
+pub fn set_status(ctx: &Context, args: SetStatusArgs) -> Result<Value> {
+    let authority = ctx.payer()?.pubkey();
+    let has_roles = ctx.global()?.global_authority != authority;
+    let params = GlobalSettingsInput {
+        initial_virtual_token_reserves: None,
+        initial_virtual_sol_reserves: None,
+        initial_real_token_reserves: None,
+        token_total_supply: None,
+        mint_decimals: None,
+        migrate_fee_amount: None,
+        fee_receiver: None,
+        status: Some(ProgramStatus::from(args.status)),
+        whitelist_enabled: None,
+        fee_schedule: None,
+    };
+    let instruction = instructions::update_global_settings(&authority, params, has_roles);
+    let outcome = ctx.execute(&[instruction], &[])?;
+    Ok(merge(json!({ "status": format!("{:?}", args.status) }), outcome))
+}
@@ -0,0 +1,157 @@
+use anchor_lang::{ prelude::Pubkey, Discriminator };
+use anyhow::Result;
+use bonding_curve_sdk::{
+    accounts,
+    bonding_curve::{ BondingCurve, Global },
+    pda,
+    quote,
+    PROGRAM_ID,
+};
+use clap::Args;
+use serde_json::{ json, Value };
+
+use super::{ fee_schedule_json, Context };
+
+#[derive(Args, Debug)]
+pub struct StatusArgs {
+    /// Mint of the curve to show, the global settings without one
+    pub mint: Option<Pubkey>,
+}
+
+#[derive(Args, Debug)]
+pub struct ListArgs {
+    /// Only curves launched by this creator
+    #[arg(long)]
+    pub creator: Option<Pubkey>,
+    /// Hide completed curves
+    #[arg(long)]
+    pub active: bool,
+}
+
+pub fn status(ctx: &Context, args: StatusArgs) -> Result<Value> {
+    let Some(mint) = args.mint else {
+        return Ok(global_json(&ctx.global()?));
+    };
+    let curve = ctx.bonding_curve(&mint)?;
+    let slot = ctx.rpc.get_slot()?;
+    Ok(
+        json!({
+            "bonding_curve": pda::bonding_curve_address(&mint).to_string(),
+            "mint": curve.mint.to_string(),
+            "creator": curve.creator.to_string(),
+            "complete": curve.complete,
+            "completion_reason": curve.completion_reason.map(|reason| format!("{:?}", reason)),
+            "migrated": curve.migrated,
+            "refunding": curve.refunding,
+            "virtual_sol_reserves": curve.virtual_sol_reserves,
+            "virtual_token_reserves": curve.virtual_token_reserves,
+            "real_sol_reserves": curve.real_sol_reserves,
+            "real_token_reserves": curve.real_token_reserves,
+            "token_total_supply": curve.token_total_supply,
+            "mint_decimals": curve.mint_decimals,
+            // Lamports per whole token, scaled by `BondingCurve::PRICE_SCALE`
+            "price_per_token": curve
+                .price_per_token(curve.virtual_sol_reserves, curve.virtual_token_reserves)?
+                .to_string(),
+            "market_cap_lamports": curve.market_cap_lamports()?,
+            "market_cap_target_lamports": curve.market_cap_target_lamports,
+            "sol_raise_target": curve.sol_raise_target,
+            "hard_cap": curve.hard_cap,
+            "start_slot": curve.start_slot,
+            "start_time": curve.start_time,
+            "end_time": curve.end_time,
+            "slot": slot,
+            "fee_bps": quote::fee_bps(&curve, slot)?,
+            "fee_schedule": fee_schedule_json(&curve.fee_schedule),
+            "realm": curve.realm_pubkey.to_string(),
+            "launch_config": curve.launch_config.map(|launch_config| launch_config.to_string()),
+        })
+    )
+}
+
+pub fn list(ctx: &Context, args: ListArgs) -> Result<Value> {
+    let mut curves = decode_curves(
+        ctx.rpc.get_program_accounts(&PROGRAM_ID, BondingCurve::DISCRIMINATOR)?
+    );
+    curves.retain(|(_, curve)| {
+        args.creator.is_none_or(|creator| curve.creator == creator) &&
+            !(args.active && curve.complete)
+    });
+    curves.sort_by_key(|(_, curve)| curve.start_slot);
+
+    Ok(
+        curves
+            .iter()
+            .map(|(address, curve)| {
+                json!({
+                    "bonding_curve": address.to_string(),
+                    "mint": curve.mint.to_string(),
+                    "creator": curve.creator.to_string(),
+                    "start_slot": curve.start_slot,
+                    "real_sol_reserves": curve.real_sol_reserves,
+                    "sol_raise_target": curve.sol_raise_target,
+                    "complete": curve.complete,
+                    "migrated": curve.migrated,
+                })
+            })
+            .collect()
+    )
+}
+
+/// Decodes the listed curve accounts. One that doesn't decode (e.g. a legacy curve
+/// still waiting for `migrate_sol_vault`) is reported on stderr and skipped, so it
+/// doesn't hide the others.
+fn decode_curves(accounts: Vec<(Pubkey, Vec<u8>)>) -> Vec<(Pubkey, BondingCurve)> {
+    accounts
+        .into_iter()
+        .filter_map(|(address, data)| {
+            match accounts::decode_bonding_curve(&data) {
+                Ok(curve) => Some((address, curve)),
+                Err(err) => {
+                    eprintln!("warning: skipping bonding curve {}: {}", address, err);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+fn global_json(global: &Global) -> Value {
+    json!({
+        "global": pda::global_address().to_string(),
+        "status": format!("{:?}", global.status),
+        "global_authority": global.global_authority.to_string(),
+        "pending_authority": global.pending_authority.map(|authority| authority.to_string()),
+        "fee_receiver": global.fee_receiver.to_string(),
+        "migrate_fee_amount": global.migrate_fee_amount,
+        "initial_virtual_token_reserves": global.initial_virtual_token_reserves,
+        "initial_virtual_sol_reserves": global.initial_virtual_sol_reserves,
+        "initial_real_token_reserves": global.initial_real_token_reserves,
+        "token_total_supply": global.token_total_supply,
+        "mint_decimals": global.mint_decimals,
+        "whitelist_enabled": global.whitelist_enabled,
+        "fee_schedule": fee_schedule_json(&global.fee_schedule),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AccountSerialize;
+
+    #[test]
+    fn list_skips_curves_that_dont_decode() {
+        let curve = BondingCurve { start_slot: 42, ..BondingCurve::default() };
+        let mut data = Vec::new();
+        curve.try_serialize(&mut data).unwrap();
+        let (good, bad) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        // A truncated account, like a curve in the pre-vault layout
+        let decoded = decode_curves(
+            vec![(bad, data[..data.len() - 1].to_vec()), (good, data.clone())]
+        );
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].0, good);
+        assert_eq!(decoded[0].1.start_slot, 42);
+    }
+}
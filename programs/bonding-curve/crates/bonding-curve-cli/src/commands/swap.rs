@@ -0,0 +1,135 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::{ Context as _, Result };
+use bonding_curve_sdk::{
+    bonding_curve::{ math::{ self, Rounding, BPS_DENOMINATOR }, SwapParams },
+    instructions,
+    quote::{ self, BuyQuote, SellQuote },
+};
+use clap::{ Args, ValueEnum };
+use serde_json::{ json, Value };
+use solana_signer::Signer;
+
+use super::{ merge, Context };
+
+#[derive(Args, Debug)]
+pub struct BuyArgs {
+    pub mint: Pubkey,
+    /// Lamports to spend, fee included
+    pub lamports: u64,
+    /// Accepted shortfall from the quoted token amount
+    #[arg(long, default_value_t = 100)]
+    pub slippage_bps: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct SellArgs {
+    pub mint: Pubkey,
+    /// Tokens to sell, in base units
+    pub tokens: u64,
+    /// Accepted shortfall from the quoted lamports after the fee
+    #[arg(long, default_value_t = 100)]
+    pub slippage_bps: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct QuoteArgs {
+    pub mint: Pubkey,
+    #[arg(value_enum)]
+    pub side: Side,
+    /// Lamports to spend for buys, tokens in base units for sells
+    pub amount: u64,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+pub fn buy(ctx: &Context, args: BuyArgs) -> Result<Value> {
+    let global = ctx.global()?;
+    let curve = ctx.bonding_curve(&args.mint)?;
+    let quote = quote::quote_buy(&curve, args.lamports, ctx.rpc.get_slot()?)?;
+
+    let min_out_amount = min_out(quote.token_amount, args.slippage_bps)?;
+    let instruction = instructions::swap(
+        &ctx.payer()?.pubkey(),
+        &args.mint,
+        &global.fee_receiver,
+        curve.launch_config,
+        SwapParams {
+            base_in: false,
+            amount: args.lamports,
+            min_out_amount,
+            exact_out: false,
+            max_in_amount: 0,
+        }
+    );
+
+    let outcome = ctx.execute(&[instruction], &[])?;
+    let quote = buy_quote_json(&quote);
+    Ok(merge(json!({ "quote": quote, "min_out_amount": min_out_amount }), outcome))
+}
+
+pub fn sell(ctx: &Context, args: SellArgs) -> Result<Value> {
+    let global = ctx.global()?;
+    let curve = ctx.bonding_curve(&args.mint)?;
+    let quote = quote::quote_sell(&curve, args.tokens, ctx.rpc.get_slot()?)?;
+
+    let min_out_amount = min_out(quote.net_sol_amount, args.slippage_bps)?;
+    let instruction = instructions::swap(
+        &ctx.payer()?.pubkey(),
+        &args.mint,
+        &global.fee_receiver,
+        curve.launch_config,
+        SwapParams {
+            base_in: true,
+            amount: args.tokens,
+            min_out_amount,
+            exact_out: false,
+            max_in_amount: 0,
+        }
+    );
+
+    let outcome = ctx.execute(&[instruction], &[])?;
+    let quote = sell_quote_json(&quote);
+    Ok(merge(json!({ "quote": quote, "min_out_amount": min_out_amount }), outcome))
+}
+
+/// Quotes against the current curve state and slot, nothing is sent
+pub fn quote(ctx: &Context, args: QuoteArgs) -> Result<Value> {
+    let curve = ctx.bonding_curve(&args.mint)?;
+    let slot = ctx.rpc.get_slot()?;
+    let quote = match args.side {
+        Side::Buy => buy_quote_json(&quote::quote_buy(&curve, args.amount, slot)?),
+        Side::Sell => sell_quote_json(&quote::quote_sell(&curve, args.amount, slot)?),
+    };
+    Ok(merge(json!({ "slot": slot, "fee_bps": quote::fee_bps(&curve, slot)? }), quote))
+}
+
+fn min_out(amount: u64, slippage_bps: u64) -> Result<u64> {
+    let kept_bps = BPS_DENOMINATOR
+        .checked_sub(slippage_bps)
+        .context("--slippage-bps can't be above 10000")?;
+    Ok(math::bps_mul(amount, kept_bps, Rounding::Down)?)
+}
+
+fn buy_quote_json(quote: &BuyQuote) -> Value {
+    json!({
+        "token_amount": quote.token_amount,
+        "gross_sol_amount": quote.gross_sol_amount,
+        "fee_lamports": quote.fee_lamports,
+        "net_sol_amount": quote.net_sol_amount,
+        "partial_fill": quote.partial_fill,
+        "completes_curve": quote.completes_curve,
+    })
+}
+
+fn sell_quote_json(quote: &SellQuote) -> Value {
+    json!({
+        "token_amount": quote.token_amount,
+        "gross_sol_amount": quote.gross_sol_amount,
+        "fee_lamports": quote.fee_lamports,
+        "net_sol_amount": quote.net_sol_amount,
+    })
+}
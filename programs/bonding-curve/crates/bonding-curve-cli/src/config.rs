@@ -0,0 +1,83 @@
+//! Settings from the Solana CLI config, the file `solana config set` writes
+
+use std::path::{ Path, PathBuf };
+
+use anyhow::{ Context, Result };
+use serde::Deserialize;
+
+const DEFAULT_RPC_URL: &str = "http://localhost:8899";
+const DEFAULT_COMMITMENT: &str = "confirmed";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SolanaConfig {
+    pub json_rpc_url: Option<String>,
+    pub keypair_path: Option<String>,
+    pub commitment: Option<String>,
+}
+
+impl SolanaConfig {
+    /// Loads `path`, or `~/.config/solana/cli/config.yml` when none is given.
+    /// A missing default config is not an error, the defaults apply instead.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let Some(path) = home_path(".config/solana/cli/config.yml") else {
+                    return Ok(Self::default());
+                };
+                if !path.exists() {
+                    return Ok(Self::default());
+                }
+                path
+            }
+        };
+        let file = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        serde_yaml::from_str(&file).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    /// `--url` wins over the config, a local validator is the fallback
+    pub fn rpc_url(&self, url: Option<&str>) -> String {
+        let url = url.or(self.json_rpc_url.as_deref()).unwrap_or(DEFAULT_RPC_URL);
+        normalize_url(url)
+    }
+
+    /// `--keypair` wins over the config, then `~/.config/solana/id.json`
+    pub fn keypair_path(&self, keypair: Option<&Path>) -> Result<PathBuf> {
+        if let Some(keypair) = keypair {
+            return Ok(keypair.to_path_buf());
+        }
+        match &self.keypair_path {
+            Some(path) => Ok(expand_home(path)),
+            None => {
+                home_path(".config/solana/id.json").context("no home directory for the keypair")
+            }
+        }
+    }
+
+    pub fn commitment(&self) -> String {
+        self.commitment.clone().unwrap_or_else(|| DEFAULT_COMMITMENT.to_string())
+    }
+}
+
+/// Resolves the cluster monikers `solana --url` accepts
+fn normalize_url(url: &str) -> String {
+    match url {
+        "l" | "localhost" => DEFAULT_RPC_URL,
+        "d" | "devnet" => "https://api.devnet.solana.com",
+        "t" | "testnet" => "https://api.testnet.solana.com",
+        "m" | "mainnet-beta" => "https://api.mainnet-beta.solana.com",
+        url => url,
+    }.to_string()
+}
+
+fn home_path(relative: &str) -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(relative))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/").and_then(home_path) {
+        Some(path) => path,
+        None => PathBuf::from(path),
+    }
+}
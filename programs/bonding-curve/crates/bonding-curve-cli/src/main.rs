@@ -0,0 +1,132 @@
+//! Operates bonding curve launches from the command line.
+//!
+//! The RPC URL, keypair and commitment come from the Solana CLI config, the
+//! same way `solana` resolves them. Amounts are raw units: lamports for SOL
+//! and base units for tokens.
+
+use std::path::PathBuf;
+
+use anyhow::{ anyhow, Result };
+use clap::{ Parser, Subcommand };
+use solana_keypair::read_keypair_file;
+
+mod commands;
+mod config;
+mod output;
+mod rpc;
+
+use commands::{
+    authority::ProposeAuthorityArgs,
+    create::CreateArgs,
+    init_global::InitGlobalArgs,
+    roles::{ RolesArgs, SetStatusArgs },
+    status::{ ListArgs, StatusArgs },
+    swap::{ BuyArgs, QuoteArgs, SellArgs },
+    Context,
+};
+use config::SolanaConfig;
+use output::OutputFormat;
+use rpc::RpcClient;
+
+#[derive(Parser, Debug)]
+#[command(name = "bonding-curve-cli", version, about)]
+struct Cli {
+    /// Solana CLI config file, `~/.config/solana/cli/config.yml` by default
+    #[arg(short = 'C', long, global = true)]
+    config: Option<PathBuf>,
+    /// RPC URL or moniker (`localhost`, `devnet`, `testnet`, `mainnet-beta`)
+    #[arg(short = 'u', long, global = true)]
+    url: Option<String>,
+    /// Signing keypair, also the payer
+    #[arg(short = 'k', long, global = true)]
+    keypair: Option<PathBuf>,
+    /// Simulate transactions instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+    #[arg(long, value_enum, default_value_t, global = true)]
+    output: OutputFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Create the global settings, the keypair becomes the global authority
+    InitGlobal(InitGlobalArgs),
+    /// Propose a new global authority, signed by the current one
+    ProposeAuthority(ProposeAuthorityArgs),
+    /// Take over the global authority, signed by the proposed key
+    AcceptAuthority,
+    /// Drop the pending global authority
+    CancelAuthorityTransfer,
+    /// Give admin roles to a key, signed by the global authority
+    GrantRoles(RolesArgs),
+    /// Take admin roles away from a key, signed by the global authority
+    RevokeRoles(RolesArgs),
+    /// Change the program status, pausers can only pause
+    SetStatus(SetStatusArgs),
+    /// Launch a bonding curve for a new mint
+    Create(CreateArgs),
+    /// Buy tokens with an exact amount of lamports, fee included
+    Buy(BuyArgs),
+    /// Sell an exact amount of tokens
+    Sell(SellArgs),
+    /// Quote a buy or a sell at the current slot
+    Quote(QuoteArgs),
+    /// Show a curve, or the global settings
+    Status(StatusArgs),
+    /// List the program's bonding curves
+    List(ListArgs),
+}
+
+impl Command {
+    fn signs(&self) -> bool {
+        !matches!(self, Command::Quote(_) | Command::Status(_) | Command::List(_))
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let format = cli.output;
+    match run(cli) {
+        Ok(value) => output::print(format, &value),
+        Err(err) => {
+            eprintln!("error: {:#}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<serde_json::Value> {
+    let config = SolanaConfig::load(cli.config.as_deref())?;
+    let keypair_path = config.keypair_path(cli.keypair.as_deref())?;
+    let payer = match read_keypair_file(&keypair_path) {
+        Ok(payer) => Some(payer),
+        // Reads work without a keypair
+        Err(_) if !cli.command.signs() => None,
+        Err(err) => {
+            return Err(anyhow!("reading keypair {}: {}", keypair_path.display(), err));
+        }
+    };
+    let ctx = Context {
+        rpc: RpcClient::new(config.rpc_url(cli.url.as_deref()), config.commitment()),
+        payer,
+        dry_run: cli.dry_run,
+    };
+
+    match cli.command {
+        Command::InitGlobal(args) => commands::init_global::run(&ctx, args),
+        Command::ProposeAuthority(args) => commands::authority::propose(&ctx, args),
+        Command::AcceptAuthority => commands::authority::accept(&ctx),
+        Command::CancelAuthorityTransfer => commands::authority::cancel(&ctx),
+        Command::GrantRoles(args) => commands::roles::grant(&ctx, args),
+        Command::RevokeRoles(args) => commands::roles::revoke(&ctx, args),
+        Command::SetStatus(args) => commands::roles::set_status(&ctx, args),
+        Command::Create(args) => commands::create::run(&ctx, args),
+        Command::Buy(args) => commands::swap::buy(&ctx, args),
+        Command::Sell(args) => commands::swap::sell(&ctx, args),
+        Command::Quote(args) => commands::swap::quote(&ctx, args),
+        Command::Status(args) => commands::status::status(&ctx, args),
+        Command::List(args) => commands::status::list(&ctx, args),
+    }
+}
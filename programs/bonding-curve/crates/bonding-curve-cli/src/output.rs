@@ -0,0 +1,61 @@
+//! Commands build a JSON value, which is printed as-is or as indented `key: value` lines
+
+use clap::ValueEnum;
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    Json,
+}
+
+pub fn print(format: OutputFormat, value: &Value) {
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(value).unwrap_or_default());
+        }
+        OutputFormat::Human => print_human(value, 0),
+    }
+}
+
+fn print_human(value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                let key = key.replace('_', " ");
+                match value {
+                    Value::Object(_) | Value::Array(_) => {
+                        println!("{}{}:", pad, key);
+                        print_human(value, indent + 2);
+                    }
+                    _ => println!("{}{}: {}", pad, key, scalar(value)),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                match item {
+                    Value::Object(_) | Value::Array(_) => {
+                        if index > 0 {
+                            println!();
+                        }
+                        print_human(item, indent);
+                    }
+                    _ => println!("{}- {}", pad, scalar(item)),
+                }
+            }
+        }
+        _ => println!("{}{}", pad, scalar(value)),
+    }
+}
+
+/// Strings without quotes and `-` for missing values
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
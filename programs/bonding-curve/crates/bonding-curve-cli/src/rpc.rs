@@ -0,0 +1,184 @@
+//! The handful of JSON-RPC methods the CLI needs
+
+use std::{ str::FromStr, thread, time::Duration };
+
+use anchor_lang::{ prelude::Pubkey, solana_program::hash::Hash };
+use anyhow::{ anyhow, bail, Context, Result };
+use base64::{ engine::general_purpose::STANDARD as BASE64, Engine };
+use serde_json::{ json, Value };
+use solana_transaction::Transaction;
+
+/// Polls for a confirmation for about a minute
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const CONFIRM_POLL_ATTEMPTS: u32 = 120;
+
+pub struct RpcClient {
+    url: String,
+    commitment: String,
+}
+
+/// Outcome of `simulateTransaction`
+pub struct Simulation {
+    pub err: Option<Value>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+impl RpcClient {
+    pub fn new(url: String, commitment: String) -> Self {
+        Self { url, commitment }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = ureq::post(&self.url)
+            .send_json(body)
+            .with_context(|| format!("{} request to {}", method, self.url))?
+            .into_json()
+            .with_context(|| format!("{} response", method))?;
+
+        if let Some(error) = response.get("error") {
+            let message = error["message"].as_str().unwrap_or("unknown error");
+            let logs = error["data"]["logs"]
+                .as_array()
+                .map(|logs| {
+                    logs.iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default();
+            bail!("{} failed: {}\n{}", method, message, logs);
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("{} returned no result", method))
+    }
+
+    pub fn get_slot(&self) -> Result<u64> {
+        let slot = self.request("getSlot", json!([{ "commitment": self.commitment }]))?;
+        slot.as_u64().context("getSlot returned no slot")
+    }
+
+    pub fn get_latest_blockhash(&self) -> Result<Hash> {
+        let result = self.request(
+            "getLatestBlockhash",
+            json!([{ "commitment": self.commitment }])
+        )?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .context("getLatestBlockhash returned no blockhash")?;
+        Ok(Hash::from_str(blockhash)?)
+    }
+
+    /// Data of the account at `address`, `None` if it doesn't exist
+    pub fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let result = self.request(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": self.commitment }])
+        )?;
+        if result["value"].is_null() {
+            return Ok(None);
+        }
+        decode_data(&result["value"]["data"]).map(Some)
+    }
+
+    /// Accounts of `program_id` whose data starts with `discriminator`
+    pub fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        discriminator: &[u8]
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let filter = json!({
+            "memcmp": { "offset": 0, "bytes": BASE64.encode(discriminator), "encoding": "base64" },
+        });
+        let result = self.request(
+            "getProgramAccounts",
+            json!([
+                program_id.to_string(),
+                { "encoding": "base64", "commitment": self.commitment, "filters": [filter] },
+            ])
+        )?;
+        result
+            .as_array()
+            .context("getProgramAccounts returned no accounts")?
+            .iter()
+            .map(|account| {
+                let address = account["pubkey"]
+                    .as_str()
+                    .context("getProgramAccounts returned an account without a pubkey")?;
+                Ok((Pubkey::from_str(address)?, decode_data(&account["account"]["data"])?))
+            })
+            .collect()
+    }
+
+    pub fn simulate_transaction(&self, transaction: &Transaction) -> Result<Simulation> {
+        let result = self.request(
+            "simulateTransaction",
+            json!([
+                encode_transaction(transaction)?,
+                { "encoding": "base64", "commitment": self.commitment, "sigVerify": true },
+            ])
+        )?;
+        let value = &result["value"];
+        Ok(Simulation {
+            err: Some(value["err"].clone()).filter(|err| !err.is_null()),
+            logs: value["logs"]
+                .as_array()
+                .map(|logs| logs.iter().filter_map(Value::as_str).map(String::from).collect())
+                .unwrap_or_default(),
+            units_consumed: value["unitsConsumed"].as_u64(),
+        })
+    }
+
+    /// Sends `transaction` and waits until it reaches the configured commitment
+    pub fn send_and_confirm_transaction(&self, transaction: &Transaction) -> Result<String> {
+        let result = self.request(
+            "sendTransaction",
+            json!([
+                encode_transaction(transaction)?,
+                { "encoding": "base64", "preflightCommitment": self.commitment },
+            ])
+        )?;
+        let signature = result.as_str().context("sendTransaction returned no signature")?;
+
+        for _ in 0..CONFIRM_POLL_ATTEMPTS {
+            let statuses = self.request("getSignatureStatuses", json!([[signature]]))?;
+            let status = &statuses["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    bail!("transaction {} failed: {}", signature, status["err"]);
+                }
+                if self.reached_commitment(status["confirmationStatus"].as_str()) {
+                    return Ok(signature.to_string());
+                }
+            }
+            thread::sleep(CONFIRM_POLL_INTERVAL);
+        }
+        bail!("transaction {} was not confirmed in time", signature)
+    }
+
+    fn reached_commitment(&self, confirmation_status: Option<&str>) -> bool {
+        matches!(
+            (self.commitment.as_str(), confirmation_status),
+            (_, Some("finalized")) |
+                ("processed" | "confirmed", Some("confirmed")) |
+                ("processed", Some("processed"))
+        )
+    }
+}
+
+fn encode_transaction(transaction: &Transaction) -> Result<String> {
+    Ok(BASE64.encode(bincode::serialize(transaction)?))
+}
+
+/// Account data comes back as `[data, "base64"]`
+fn decode_data(data: &Value) -> Result<Vec<u8>> {
+    let data = data[0].as_str().context("account data is not base64 encoded")?;
+    Ok(BASE64.decode(data)?)
+}
@@ -0,0 +1,135 @@
+//! Runs the CLI end to end against a local `solana-test-validator`, started the way
+//! the README describes (program and token metadata loaded):
+//!
+//! ```sh
+//! cargo test -p bonding-curve-cli --test local_validator -- --ignored
+//! ```
+//!
+//! `BONDING_CURVE_CLI_TEST_URL` points it at another local RPC port.
+
+use std::{ env, path::Path, process::Command, thread, time::Duration };
+
+use serde_json::{ json, Value };
+use solana_keypair::{ write_keypair_file, Keypair };
+use solana_signer::Signer;
+
+const DEFAULT_URL: &str = "http://127.0.0.1:8899";
+
+fn rpc_url() -> String {
+    env::var("BONDING_CURVE_CLI_TEST_URL").unwrap_or_else(|_| DEFAULT_URL.to_string())
+}
+
+fn rpc(url: &str, method: &str, params: Value) -> Value {
+    let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    let response: Value = ureq::post(url).send_json(body).unwrap().into_json().unwrap();
+    assert!(response.get("error").is_none(), "{} failed: {}", method, response["error"]);
+    response["result"].clone()
+}
+
+/// Runs the CLI with JSON output and returns what it printed
+fn cli(url: &str, keypair: &Path, args: &[&str]) -> Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_bonding-curve-cli"))
+        .args(["--url", url, "--keypair"])
+        .arg(keypair)
+        .args(["--output", "json"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "bonding-curve-cli {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn airdrop(url: &str, keypair: &Keypair, lamports: u64) {
+    rpc(url, "requestAirdrop", json!([keypair.pubkey().to_string(), lamports]));
+    for _ in 0..60 {
+        let balance = rpc(url, "getBalance", json!([keypair.pubkey().to_string()]));
+        if balance["value"].as_u64().unwrap_or_default() >= lamports {
+            return;
+        }
+        thread::sleep(Duration::from_millis(500));
+    }
+    panic!("airdrop to {} never landed", keypair.pubkey());
+}
+
+#[test]
+#[ignore = "needs a solana-test-validator with the program deployed"]
+fn operates_a_launch_on_a_local_validator() {
+    let url = rpc_url();
+    // Airdrops and throwaway keys only make sense on a local cluster
+    assert!(
+        url.contains("127.0.0.1") || url.contains("localhost"),
+        "{} is not a local validator",
+        url
+    );
+    assert_eq!(rpc(&url, "getHealth", json!([])), "ok");
+
+    let dir = env::temp_dir().join(format!("bonding-curve-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let keypair = Keypair::new();
+    let keypair_path = dir.join("payer.json");
+    write_keypair_file(&keypair, &keypair_path).unwrap();
+    airdrop(&url, &keypair, 100_000_000_000);
+    let payer = keypair.pubkey().to_string();
+
+    // A validator started with `--reset` has no global settings yet
+    let has_global = Command::new(env!("CARGO_BIN_EXE_bonding-curve-cli"))
+        .args(["--url", &url, "status"])
+        .output()
+        .unwrap()
+        .status.success();
+    if !has_global {
+        let init = cli(&url, &keypair_path, &["init-global"]);
+        assert_eq!(init["global_authority"], payer);
+    }
+
+    let realm = Keypair::new().pubkey().to_string();
+    let created = cli(
+        &url,
+        &keypair_path,
+        &[
+            "create",
+            "--name",
+            "Test",
+            "--symbol",
+            "TST",
+            "--uri",
+            "https://example.com/tst.json",
+            "--sol-raise-target",
+            "85000000000",
+            "--realm",
+            &realm,
+        ]
+    );
+    let mint = created["mint"].as_str().unwrap().to_string();
+    assert_eq!(created["creator"], payer);
+
+    let quote = cli(&url, &keypair_path, &["quote", &mint, "buy", "1000000000"]);
+    let bought = cli(&url, &keypair_path, &["buy", &mint, "1000000000"]);
+    assert!(bought["signature"].is_string());
+    let token_amount = bought["quote"]["token_amount"].as_u64().unwrap();
+    assert!(token_amount > 0);
+    assert!(quote["token_amount"].as_u64().unwrap() > 0);
+
+    let status = cli(&url, &keypair_path, &["status", &mint]);
+    assert_eq!(status["mint"], mint.as_str());
+    assert!(status["real_sol_reserves"].as_u64().unwrap() > 0);
+
+    let listed = cli(&url, &keypair_path, &["list", "--creator", &payer]);
+    assert!(
+        listed
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|curve| curve["mint"] == mint.as_str())
+    );
+
+    let sold = cli(&url, &keypair_path, &["sell", &mint, &(token_amount / 2).to_string()]);
+    assert!(sold["signature"].is_string());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
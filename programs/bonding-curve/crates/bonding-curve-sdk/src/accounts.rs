@@ -0,0 +1,27 @@
+use anchor_lang::{ AccountDeserialize, Result };
+use bonding_curve::{ BondingCurve, Global, LaunchConfig, Roles, Whitelist };
+
+/// Decodes raw account data, checking the account discriminator
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_global(data: &[u8]) -> Result<Global> {
+    decode(data)
+}
+
+pub fn decode_bonding_curve(data: &[u8]) -> Result<BondingCurve> {
+    decode(data)
+}
+
+pub fn decode_launch_config(data: &[u8]) -> Result<LaunchConfig> {
+    decode(data)
+}
+
+pub fn decode_roles(data: &[u8]) -> Result<Roles> {
+    decode(data)
+}
+
+pub fn decode_whitelist(data: &[u8]) -> Result<Whitelist> {
+    decode(data)
+}
@@ -0,0 +1,251 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{ instruction::Instruction, sysvar },
+    system_program,
+    InstructionData,
+    ToAccountMetas,
+};
+use bonding_curve::{
+    accounts,
+    instruction,
+    CreateBondingCurveParams,
+    GlobalSettingsInput,
+    LaunchConfigParams,
+    QuoteParams,
+    SwapParams,
+};
+
+use crate::{ pda, PROGRAM_ID };
+
+fn program_instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Creates the global settings account, `admin` becomes the global authority
+pub fn initialize(admin: &Pubkey, params: GlobalSettingsInput) -> Instruction {
+    program_instruction(
+        accounts::Initialize {
+            admin: *admin,
+            global: pda::global_address(),
+            system_program: system_program::ID,
+            event_authority: pda::event_authority_address(),
+            program: PROGRAM_ID,
+        },
+        instruction::Initialize { params }
+    )
+}
+
+/// Updates the settings that are `Some` in `params`. `authority` is the global
+/// authority, or a member holding the roles the change needs with `has_roles`.
+pub fn update_global_settings(
+    authority: &Pubkey,
+    params: GlobalSettingsInput,
+    has_roles: bool
+) -> Instruction {
+    program_instruction(
+        accounts::UpdateGlobalSettings {
+            authority: *authority,
+            global: pda::global_address(),
+            roles: has_roles.then(|| pda::roles_address(authority)),
+            event_authority: pda::event_authority_address(),
+            program: PROGRAM_ID,
+        },
+        instruction::UpdateGlobalSettings { params }
+    )
+}
+
+/// Adds `permissions` (`Roles::PAUSER` and so on) to `member`, signed by the global authority
+pub fn grant_roles(authority: &Pubkey, member: &Pubkey, permissions: u8) -> Instruction {
+    program_instruction(
+        accounts::GrantRoles {
+            authority: *authority,
+            global: pda::global_address(),
+            roles: pda::roles_address(member),
+            system_program: system_program::ID,
+            event_authority: pda::event_authority_address(),
+            program: PROGRAM_ID,
+        },
+        instruction::GrantRoles { member: *member, permissions }
+    )
+}
+
+/// Removes `permissions` from `member`, signed by the global authority
+pub fn revoke_roles(authority: &Pubkey, member: &Pubkey, permissions: u8) -> Instruction {
+    program_instruction(
+        accounts::RevokeRoles {
+            authority: *authority,
+            global: pda::global_address(),
+            roles: pda::roles_address(member),
+            event_authority: pda::event_authority_address(),
+            program: PROGRAM_ID,
+        },
+        instruction::RevokeRoles { member: *member, permissions }
+    )
+}
+
+/// Proposes `new_authority` as the global authority, signed by the current one
+pub fn propose_authority(authority: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    program_instruction(
+        accounts::ProposeAuthority {
+            authority: *authority,
+            global: pda::global_address(),
+            event_authority: pda::event_authority_address(),
+            program: PROGRAM_ID,
+        },
+        instruction::ProposeAuthority { new_authority: *new_authority }
+    )
+}
+
+/// Takes over the global authority, signed by the proposed authority
+pub fn accept_authority(new_authority: &Pubkey) -> Instruction {
+    program_instruction(
+        accounts::AcceptAuthority {
+            new_authority: *new_authority,
+            global: pda::global_address(),
+            event_authority: pda::event_authority_address(),
+            program: PROGRAM_ID,
+        },
+        instruction::AcceptAuthority {}
+    )
+}
+
+/// Drops the pending authority, signed by the global authority
+pub fn cancel_authority_transfer(authority: &Pubkey) -> Instruction {
+    program_instruction(
+        accounts::CancelAuthorityTransfer {
+            authority: *authority,
+            global: pda::global_address(),
+            event_authority: pda::event_authority_address(),
+            program: PROGRAM_ID,
+        },
+        instruction::CancelAuthorityTransfer {}
+    )
+}
+
+/// Creates launch config `id`, signed by the global authority or a config
+/// manager with `has_roles`
+pub fn create_launch_config(
+    authority: &Pubkey,
+    id: u64,
+    params: LaunchConfigParams,
+    has_roles: bool
+) -> Instruction {
+    program_instruction(
+        accounts::CreateLaunchConfig {
+            authority: *authority,
+            global: pda::global_address(),
+            roles: has_roles.then(|| pda::roles_address(authority)),
+            launch_config: pda::launch_config_address(id),
+            system_program: system_program::ID,
+            event_authority: pda::event_authority_address(),
+            program: PROGRAM_ID,
+        },
+        instruction::CreateLaunchConfig { id, params }
+    )
+}
+
+/// Replaces the parameters of launch config `id`, signed by the global
+/// authority or a config manager with `has_roles`
+pub fn update_launch_config(
+    authority: &Pubkey,
+    id: u64,
+    params: LaunchConfigParams,
+    has_roles: bool
+) -> Instruction {
+    program_instruction(
+        accounts::UpdateLaunchConfig {
+            authority: *authority,
+            global: pda::global_address(),
+            roles: has_roles.then(|| pda::roles_address(authority)),
+            launch_config: pda::launch_config_address(id),
+            event_authority: pda::event_authority_address(),
+            program: PROGRAM_ID,
+        },
+        instruction::UpdateLaunchConfig { params }
+    )
+}
+
+/// Launches a curve for a new `mint`, which has to sign alongside `creator`.
+/// Pass `whitelisted` when the global whitelist is enabled, and the address of
+/// a launch config to launch from it instead of the global settings.
+pub fn create_bonding_curve(
+    creator: &Pubkey,
+    mint: &Pubkey,
+    params: CreateBondingCurveParams,
+    whitelisted: bool,
+    launch_config: Option<Pubkey>
+) -> Instruction {
+    let bonding_curve = pda::bonding_curve_address(mint);
+    program_instruction(
+        accounts::CreateBondingCurve {
+            mint: *mint,
+            creator: *creator,
+            bonding_curve,
+            sol_vault: pda::sol_vault_address(&bonding_curve),
+            bonding_curve_token_account: pda::bonding_curve_token_account_address(mint),
+            global: pda::global_address(),
+            whitelist: whitelisted.then(|| pda::whitelist_address(creator)),
+            launch_config,
+            metadata: pda::metadata_address(mint),
+            rent: sysvar::rent::ID,
+            system_program: system_program::ID,
+            token_program: anchor_spl::token::ID,
+            token_metadata_program: anchor_spl::metadata::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            event_authority: pda::event_authority_address(),
+            program: PROGRAM_ID,
+        },
+        instruction::CreateBondingCurve { params }
+    )
+}
+
+/// Buys (`base_in == false`) or sells tokens of `mint`. `fee_receiver` must be
+/// the one in the global settings and `launch_config` the one the curve was
+/// created from, `BondingCurve.launch_config`.
+pub fn swap(
+    user: &Pubkey,
+    mint: &Pubkey,
+    fee_receiver: &Pubkey,
+    launch_config: Option<Pubkey>,
+    params: SwapParams
+) -> Instruction {
+    let bonding_curve = pda::bonding_curve_address(mint);
+    program_instruction(
+        accounts::Swap {
+            user: *user,
+            global: pda::global_address(),
+            fee_receiver: *fee_receiver,
+            mint: *mint,
+            bonding_curve,
+            launch_config,
+            sol_vault: pda::sol_vault_address(&bonding_curve),
+            bonding_curve_token_account: pda::bonding_curve_token_account_address(mint),
+            user_token_account: pda::token_account_address(user, mint),
+            system_program: system_program::ID,
+            token_program: anchor_spl::token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            clock: sysvar::clock::ID,
+            event_authority: pda::event_authority_address(),
+            program: PROGRAM_ID,
+        },
+        instruction::Swap { params }
+    )
+}
+
+/// Read-only quote of a swap on `mint`. Simulate it and decode `QuoteResult`
+/// from the return data. `launch_config` is the same as for `swap`.
+pub fn quote(mint: &Pubkey, launch_config: Option<Pubkey>, params: QuoteParams) -> Instruction {
+    program_instruction(
+        accounts::Quote {
+            global: pda::global_address(),
+            mint: *mint,
+            bonding_curve: pda::bonding_curve_address(mint),
+            launch_config,
+        },
+        instruction::Quote { params }
+    )
+}
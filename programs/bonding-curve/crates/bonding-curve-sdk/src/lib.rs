@@ -0,0 +1,14 @@
+//! Off-chain client for the bonding curve program.
+//!
+//! - `pda`: addresses derived from the program's seeds
+//! - `instructions`: typed builders for `initialize`, `create_bonding_curve`, `swap` and `quote`
+//! - `accounts`: decoders for the program's accounts
+//! - `quote`: swap quotes computed with the program's own math
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+pub mod quote;
+
+pub use bonding_curve;
+pub use bonding_curve::ID as PROGRAM_ID;
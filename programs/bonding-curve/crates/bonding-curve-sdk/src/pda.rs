@@ -0,0 +1,70 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    metadata::mpl_token_metadata,
+};
+use bonding_curve::{
+    BondingCurve,
+    Global,
+    LaunchConfig,
+    Roles,
+    Whitelist,
+    EVENT_AUTHORITY_SEED,
+};
+
+use crate::PROGRAM_ID;
+
+pub fn global_address() -> Pubkey {
+    Pubkey::find_program_address(&[Global::SEED_PREFIX.as_bytes()], &PROGRAM_ID).0
+}
+
+pub fn bonding_curve_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[BondingCurve::SEED_PREFIX.as_bytes(), mint.as_ref()],
+        &PROGRAM_ID
+    ).0
+}
+
+/// System owned account holding the pool SOL of a curve
+pub fn sol_vault_address(bonding_curve: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), bonding_curve.as_ref()],
+        &PROGRAM_ID
+    ).0
+}
+
+pub fn whitelist_address(creator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[Whitelist::SEED_PREFIX.as_bytes(), creator.as_ref()],
+        &PROGRAM_ID
+    ).0
+}
+
+pub fn roles_address(member: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[Roles::SEED_PREFIX.as_bytes(), member.as_ref()], &PROGRAM_ID).0
+}
+
+pub fn launch_config_address(id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[LaunchConfig::SEED_PREFIX.as_bytes(), &id.to_le_bytes()],
+        &PROGRAM_ID
+    ).0
+}
+
+pub fn event_authority_address() -> Pubkey {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &PROGRAM_ID).0
+}
+
+/// Associated token account of `owner` for `mint` under the SPL token program
+pub fn token_account_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, &anchor_spl::token::ID)
+}
+
+/// Token account holding the curve's reserves
+pub fn bonding_curve_token_account_address(mint: &Pubkey) -> Pubkey {
+    token_account_address(&bonding_curve_address(mint), mint)
+}
+
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    mpl_token_metadata::accounts::Metadata::find_pda(mint).0
+}
@@ -0,0 +1,203 @@
+//! Swap quotes with the same math and rounding as the program.
+//!
+//! The pricing, fill and completion rules come from `bonding_curve::curve_math`,
+//! the log-free functions the program's own `BondingCurve` methods are built on.
+
+use anchor_lang::prelude::*;
+use bonding_curve::{ curve_math, errors::ContractError, math, BondingCurve };
+
+pub use bonding_curve::curve_math::fee_bps;
+
+/// Fee on `amount` lamports at `slot`, see `BondingCurve::calculate_fee`
+pub fn calculate_fee(curve: &BondingCurve, amount: u64, slot: u64) -> Result<u64> {
+    curve_math::fee_lamports(amount, fee_bps(curve, slot)?)
+}
+
+/// Gross amount and fee that leave `net_amount` after the fee at `slot`,
+/// see `BondingCurve::gross_for_net_after_fee`
+pub fn gross_for_net_after_fee(
+    curve: &BondingCurve,
+    net_amount: u64,
+    slot: u64
+) -> Result<(u64, u64)> {
+    curve_math::gross_for_net_after_fee(net_amount, fee_bps(curve, slot)?)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuyQuote {
+    /// Lamports the buyer is charged, fee included
+    pub gross_sol_amount: u64,
+    pub fee_lamports: u64,
+    /// Lamports that go into the curve
+    pub net_sol_amount: u64,
+    pub token_amount: u64,
+    /// The hard cap or the last tokens cut the buy short, only `gross_sol_amount` is charged
+    pub partial_fill: bool,
+    /// The buy would complete the curve
+    pub completes_curve: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SellQuote {
+    pub token_amount: u64,
+    /// Lamports that leave the curve
+    pub gross_sol_amount: u64,
+    pub fee_lamports: u64,
+    /// Lamports the seller receives
+    pub net_sol_amount: u64,
+}
+
+/// Exact input buy spending `sol_amount` lamports (fee included) at `slot`,
+/// filled the way `swap` fills it
+pub fn quote_buy(curve: &BondingCurve, sol_amount: u64, slot: u64) -> Result<BuyQuote> {
+    let fee = calculate_fee(curve, sol_amount, slot)?;
+    let requested_sol_amount = math::checked_sub(sol_amount, fee)?;
+    let (net_sol_amount, token_amount) = curve_math::buy_fill(curve, requested_sol_amount)?;
+    let (_, completion_reason) = curve_math::buy_reserves(curve, net_sol_amount, token_amount)?;
+
+    let partial_fill = net_sol_amount < requested_sol_amount;
+    let (gross_sol_amount, fee_lamports) = if partial_fill {
+        gross_for_net_after_fee(curve, net_sol_amount, slot)?
+    } else {
+        (sol_amount, fee)
+    };
+
+    Ok(BuyQuote {
+        gross_sol_amount,
+        fee_lamports,
+        net_sol_amount,
+        token_amount,
+        partial_fill,
+        completes_curve: completion_reason.is_some(),
+    })
+}
+
+/// Exact input sell of `token_amount` tokens at `slot`
+pub fn quote_sell(curve: &BondingCurve, token_amount: u64, slot: u64) -> Result<SellQuote> {
+    let gross_sol_amount = curve_math::sol_for_sell_tokens(curve, token_amount)?;
+    require!(gross_sol_amount > 0, ContractError::ZeroOutput);
+    curve_math::sell_reserves(curve, token_amount, gross_sol_amount)?;
+
+    let fee_lamports = calculate_fee(curve, gross_sol_amount, slot)?;
+    Ok(SellQuote {
+        token_amount,
+        gross_sol_amount,
+        fee_lamports,
+        net_sol_amount: math::checked_sub(gross_sol_amount, fee_lamports)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bonding_curve::FeeSchedule;
+
+    const START_SLOT: u64 = 1_000;
+
+    fn clock_at(slot: u64) -> Clock {
+        Clock { slot, ..Clock::default() }
+    }
+
+    fn curve() -> BondingCurve {
+        BondingCurve {
+            virtual_sol_reserves: 30_000_000_000,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            real_sol_reserves: 0,
+            real_token_reserves: 793_100_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+            sol_raise_target: 85_000_000_000,
+            start_slot: START_SLOT,
+            fee_schedule: FeeSchedule::default(),
+            ..BondingCurve::default()
+        }
+    }
+
+    #[test]
+    fn fees_match_the_program() {
+        let curve = curve();
+        for elapsed in [0, 149, 150, 151, 200, 249, 250, 10_000] {
+            let slot = START_SLOT + elapsed;
+            let clock = clock_at(slot);
+            assert_eq!(fee_bps(&curve, slot).unwrap(), curve.fee_bps(&clock).unwrap());
+            assert_eq!(
+                calculate_fee(&curve, 1_234_567_891, slot).unwrap(),
+                curve.calculate_fee(1_234_567_891, &clock).unwrap()
+            );
+            assert_eq!(
+                gross_for_net_after_fee(&curve, 1_234_567_891, slot).unwrap(),
+                curve.gross_for_net_after_fee(1_234_567_891, &clock).unwrap()
+            );
+        }
+        // Before launch the launch fee applies
+        assert_eq!(fee_bps(&curve, 0).unwrap(), 9_900);
+    }
+
+    #[test]
+    fn buy_quote_matches_apply_buy() {
+        let slot = START_SLOT + 300;
+        let clock = clock_at(slot);
+        // A regular buy, then one past the hard cap
+        for (hard_cap, sol_amount) in [(false, 1_000_000_000), (true, 200_000_000_000)] {
+            let curve = BondingCurve { hard_cap, ..curve() };
+            let quote = quote_buy(&curve, sol_amount, slot).unwrap();
+
+            let fee = curve.calculate_fee(sol_amount, &clock).unwrap();
+            let mut bought = curve.clone();
+            let result = bought.apply_buy(sol_amount - fee).unwrap();
+            assert_eq!(quote.token_amount, result.token_amount);
+            assert_eq!(quote.net_sol_amount, result.sol_amount);
+            assert_eq!(quote.partial_fill, result.is_partial_fill());
+            // Only the capped buy reaches the raise target
+            assert_eq!(quote.completes_curve, hard_cap);
+            assert_eq!(quote.completes_curve, bought.complete);
+            assert_eq!(quote.gross_sol_amount, quote.net_sol_amount + quote.fee_lamports);
+        }
+
+        let hard_capped = BondingCurve { hard_cap: true, ..curve() };
+        let capped = quote_buy(&hard_capped, 200_000_000_000, slot).unwrap();
+        assert!(capped.partial_fill);
+        assert_eq!(capped.net_sol_amount, 85_000_000_000);
+        assert!(capped.gross_sol_amount < 200_000_000_000);
+    }
+
+    #[test]
+    fn sell_quote_matches_apply_sell() {
+        let slot = START_SLOT + 300;
+        let mut curve = curve();
+        curve.apply_buy(10_000_000_000).unwrap();
+
+        let quote = quote_sell(&curve, 100_000_000_000, slot).unwrap();
+        let result = curve.clone().apply_sell(100_000_000_000).unwrap();
+        assert_eq!(quote.gross_sol_amount, result.sol_amount);
+        let fee = curve.calculate_fee(result.sol_amount, &clock_at(slot)).unwrap();
+        assert_eq!(quote.fee_lamports, fee);
+        assert_eq!(quote.net_sol_amount, quote.gross_sol_amount - quote.fee_lamports);
+
+        // More than the curve holds
+        let err = quote_sell(&curve, curve.real_token_reserves * 10, slot).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientCurveReserves.into());
+    }
+
+    #[test]
+    fn buy_quote_flags_every_completion_trigger() {
+        let slot = START_SLOT + 300;
+        let clock = clock_at(slot);
+        let market_cap = curve().market_cap_lamports().unwrap();
+        let sol_target = BondingCurve { sol_raise_target: 1_000_000_000, ..curve() };
+        let market_cap_target = BondingCurve {
+            sol_raise_target: 0,
+            market_cap_target_lamports: Some(market_cap + market_cap / 100),
+            ..curve()
+        };
+        let sold_out = BondingCurve { sol_raise_target: 0, real_token_reserves: 1_000, ..curve() };
+
+        for curve in [sol_target, market_cap_target, sold_out] {
+            let quote = quote_buy(&curve, 2_000_000_000, slot).unwrap();
+            let fee = curve.calculate_fee(2_000_000_000, &clock).unwrap();
+            let mut bought = curve.clone();
+            bought.apply_buy(2_000_000_000 - fee).unwrap();
+            assert!(bought.complete);
+            assert!(quote.completes_curve);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Swap math of a `BondingCurve` as pure functions.
+//!
+//! Nothing here logs or mutates the curve, so off-chain clients can quote with the
+//! exact numbers the program uses. `BondingCurve` wraps these with its `msg!`
+//! logging and writes the results back.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ContractError,
+    math::{ self, Rounding, BPS_DENOMINATOR },
+    BondingCurve,
+    CompletionReason,
+};
+
+/// Reserves of a curve after a trade
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reserves {
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+}
+
+/// Tokens out for `sol_amount` lamports in.
+///
+/// Reserves are kept in base units (lamports and raw token amounts), so the
+/// constant product holds without rescaling for `mint_decimals`. The full
+/// u64 x u64 product fits in a u128 and the result is rounded down, so any
+/// dust stays in the pool.
+pub fn tokens_for_buy_sol(curve: &BondingCurve, sol_amount: u64) -> Result<u64> {
+    require!(sol_amount > 0, ContractError::MinSwap);
+    // tokens_out = virtual_token_reserves * sol_in / (virtual_sol_reserves + sol_in)
+    let denominator = math::checked_add(curve.virtual_sol_reserves, sol_amount)?;
+    math::mul_div(curve.virtual_token_reserves, sol_amount, denominator, Rounding::Down)
+}
+
+/// Lamports needed to take `token_amount` tokens out of the curve.
+///
+/// Inverse of `tokens_for_buy_sol`, rounded up so the pool never
+/// hands out tokens for less than the constant product asks.
+pub fn sol_for_buy_tokens(curve: &BondingCurve, token_amount: u64) -> Result<u64> {
+    require!(token_amount > 0, ContractError::MinSwap);
+    require!(
+        token_amount < curve.virtual_token_reserves,
+        ContractError::InsufficientCurveReserves
+    );
+    // sol_in = ceil(virtual_sol_reserves * tokens_out / (virtual_token_reserves - tokens_out))
+    let denominator = math::checked_sub(curve.virtual_token_reserves, token_amount)?;
+    math::mul_div(curve.virtual_sol_reserves, token_amount, denominator, Rounding::Up)
+}
+
+/// Lamports out for `token_amount` tokens in, rounded down in favor of the pool.
+pub fn sol_for_sell_tokens(curve: &BondingCurve, token_amount: u64) -> Result<u64> {
+    require!(token_amount > 0, ContractError::MinSwap);
+    // sol_out = virtual_sol_reserves * tokens_in / (virtual_token_reserves + tokens_in)
+    let denominator = math::checked_add(curve.virtual_token_reserves, token_amount)?;
+    math::mul_div(curve.virtual_sol_reserves, token_amount, denominator, Rounding::Down)
+}
+
+/// Tokens that must be sold to take `sol_amount` lamports out of the curve.
+///
+/// Inverse of `sol_for_sell_tokens`, rounded up in favor of the pool.
+pub fn tokens_for_sell_sol(curve: &BondingCurve, sol_amount: u64) -> Result<u64> {
+    require!(sol_amount > 0, ContractError::MinSwap);
+    require!(
+        sol_amount < curve.virtual_sol_reserves,
+        ContractError::InsufficientCurveReserves
+    );
+    // tokens_in = ceil(virtual_token_reserves * sol_out / (virtual_sol_reserves - sol_out))
+    let denominator = math::checked_sub(curve.virtual_sol_reserves, sol_amount)?;
+    math::mul_div(curve.virtual_token_reserves, sol_amount, denominator, Rounding::Up)
+}
+
+/// Fee in bps at `slot`. Slots before the launch pay the launch fee.
+pub fn fee_bps(curve: &BondingCurve, slot: u64) -> Result<u64> {
+    curve.fee_schedule.fee_bps(slot.saturating_sub(curve.start_slot))
+}
+
+/// Fee on `amount` lamports at `fee_bps`, rounded down
+pub fn fee_lamports(amount: u64, fee_bps: u64) -> Result<u64> {
+    math::bps_mul(amount, fee_bps, Rounding::Down)
+}
+
+/// Gross amount and fee that leave exactly `net_amount` once a `fee_bps` fee is
+/// taken. The fee absorbs the rounding, so it is never below `fee_lamports(gross)`.
+pub fn gross_for_net_after_fee(net_amount: u64, fee_bps: u64) -> Result<(u64, u64)> {
+    let net_bps = math::checked_sub(BPS_DENOMINATOR, fee_bps)?;
+    let gross_amount = math::mul_div(net_amount, BPS_DENOMINATOR, net_bps, Rounding::Up)?;
+    Ok((gross_amount, math::checked_sub(gross_amount, net_amount)?))
+}
+
+/// Implied market cap: the virtual reserve price applied to the full supply
+pub fn market_cap_lamports(
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+    token_total_supply: u64
+) -> Result<u64> {
+    math::mul_div(virtual_sol_reserves, token_total_supply, virtual_token_reserves, Rounding::Down)
+}
+
+/// Lamports and tokens an exact input buy of `sol_amount` lamports (after the fee)
+/// fills. Hard capped curves only take what is left below the target, and the
+/// last buy only pays for the remaining tokens at their live curve cost.
+pub fn buy_fill(curve: &BondingCurve, sol_amount: u64) -> Result<(u64, u64)> {
+    let mut filled_sol_amount = sol_amount;
+    if let Some(remaining) = curve.remaining_to_hard_cap() {
+        require!(remaining > 0, ContractError::RaiseTargetReached);
+        filled_sol_amount = filled_sol_amount.min(remaining);
+    }
+
+    let mut token_amount = tokens_for_buy_sol(curve, filled_sol_amount)?;
+    require!(token_amount > 0, ContractError::ZeroOutput);
+
+    // Last buy because not enough tokens left, we can't sell more tokens than we have
+    if token_amount >= curve.real_token_reserves {
+        token_amount = curve.real_token_reserves;
+        filled_sol_amount = sol_for_buy_tokens(curve, token_amount)?.min(sol_amount);
+    }
+    Ok((filled_sol_amount, token_amount))
+}
+
+/// Reserves after buying `token_amount` tokens for `sol_amount` lamports, and the
+/// first completion trigger the buy fires
+pub fn buy_reserves(
+    curve: &BondingCurve,
+    sol_amount: u64,
+    token_amount: u64
+) -> Result<(Reserves, Option<CompletionReason>)> {
+    // Share of each buy taken back out of the virtual SOL reserves
+    let virtual_sol_deduction = math::bps_mul(
+        sol_amount,
+        BondingCurve::BUY_VIRTUAL_SOL_DEDUCTION_BPS,
+        Rounding::Down
+    )?;
+    let reserves = Reserves {
+        virtual_sol_reserves: math::checked_sub(
+            math::checked_add(curve.virtual_sol_reserves, sol_amount)?,
+            virtual_sol_deduction
+        )?,
+        virtual_token_reserves: math::checked_sub(curve.virtual_token_reserves, token_amount)?,
+        real_sol_reserves: math::checked_add(curve.real_sol_reserves, sol_amount)?,
+        real_token_reserves: math::checked_sub(curve.real_token_reserves, token_amount)?,
+    };
+
+    let market_cap_reached = match curve.market_cap_target_lamports {
+        Some(market_cap_target) =>
+            market_cap_lamports(
+                reserves.virtual_sol_reserves,
+                reserves.virtual_token_reserves,
+                curve.token_total_supply
+            )? >= market_cap_target,
+        None => false,
+    };
+
+    // Reaching the SOL raise target completes the curve. Hard capped buys were
+    // already trimmed to the target by `buy_fill`, other buys may overshoot it
+    let completion_reason = if
+        curve.sol_raise_target > 0 &&
+        reserves.real_sol_reserves >= curve.sol_raise_target
+    {
+        Some(CompletionReason::SolRaiseTarget)
+    } else if reserves.real_token_reserves == 0 {
+        Some(CompletionReason::TokensSoldOut)
+    } else if market_cap_reached {
+        Some(CompletionReason::MarketCapTarget)
+    } else {
+        None
+    };
+    Ok((reserves, completion_reason))
+}
+
+/// Reserves after selling `token_amount` tokens for `sol_amount` lamports
+pub fn sell_reserves(curve: &BondingCurve, token_amount: u64, sol_amount: u64) -> Result<Reserves> {
+    // The curve can only pay out the SOL it actually holds
+    require!(sol_amount <= curve.real_sol_reserves, ContractError::InsufficientCurveReserves);
+    Ok(Reserves {
+        virtual_sol_reserves: math::checked_sub(curve.virtual_sol_reserves, sol_amount)?,
+        virtual_token_reserves: math::checked_add(curve.virtual_token_reserves, token_amount)?,
+        real_sol_reserves: math::checked_sub(curve.real_sol_reserves, sol_amount)?,
+        real_token_reserves: math::checked_add(curve.real_token_reserves, token_amount)?,
+    })
+}
@@ -14,7 +14,7 @@ pub enum ContractError {
     #[msg("Global Not Initialized")]
     NotInitialized,
 
-    #[msg("Not in Running State")]
+    #[msg("Program status does not allow this instruction")]
     ProgramNotRunning,
 
     #[msg("Bonding Curve Complete")]
@@ -75,7 +75,7 @@ pub enum ContractError {
     #[msg("Not quote mint")]
     NotSOL,
 
-    #[msg("Not equel config")]
+    #[msg("Launch config does not match the bonding curve")]
     InvalidConfig,
 
     #[msg("Arithmetic Error")]
@@ -98,4 +98,70 @@ pub enum ContractError {
 
     #[msg("Invalid realm account")]
     InvalidRealmAccount,
+
+    #[msg("Bonding curve liquidity already migrated")]
+    AlreadyMigrated,
+
+    #[msg("Invalid fee schedule")]
+    InvalidFeeSchedule,
+
+    #[msg("Nothing to skim from the bonding curve")]
+    NothingToSkim,
+
+    #[msg("Skim recipient must be the fee receiver or the DAO treasury")]
+    InvalidSkimRecipient,
+
+    #[msg("Bonding curve SOL has not been moved to its vault yet")]
+    SolVaultNotMigrated,
+
+    #[msg("Bonding curve SOL is already held in its vault")]
+    SolVaultAlreadyMigrated,
+
+    #[msg("Invalid end time")]
+    InvalidEndTime,
+
+    #[msg("Bonding curve fundraising window has ended")]
+    CurveExpired,
+
+    #[msg("Bonding curve is not refundable")]
+    CurveNotRefundable,
+
+    #[msg("Refund failed")]
+    RefundFailed,
+
+    #[msg("Market cap target must be above the starting market cap")]
+    InvalidMarketCapTarget,
+
+    #[msg("Not enough reserves on the bonding curve")]
+    InsufficientCurveReserves,
+
+    #[msg("Swap amount is too small to return anything")]
+    ZeroOutput,
+
+    #[msg("Buy exceeds the hard capped raise target")]
+    HardCapExceeded,
+
+    #[msg("SOL raise target is outside the launch config limits")]
+    InvalidSolRaiseTarget,
+
+    #[msg("No global authority transfer is pending")]
+    NoPendingAuthority,
+
+    #[msg("Signer is not the pending global authority")]
+    InvalidPendingAuthority,
+
+    #[msg("Signer does not hold the role this requires")]
+    MissingRole,
+
+    #[msg("Settings update doesn't change any setting")]
+    EmptySettingsUpdate,
+
+    #[msg("Bonding curve has not migrated its liquidity yet")]
+    BondingCurveNotMigrated,
+
+    #[msg("Nothing to withdraw from the liquidity vault")]
+    NothingToWithdraw,
+
+    #[msg("Global settings are already in the current layout")]
+    GlobalAlreadyMigrated,
 }
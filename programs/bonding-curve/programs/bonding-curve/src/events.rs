@@ -1,12 +1,69 @@
 use anchor_lang::prelude::*;
 
+use crate::{ CompletionReason, GlobalSettings, LaunchConfigParams };
+
+/// Seed of the event authority PDA `#[event_cpi]` adds to accounts structs. Anchor
+/// hardcodes it in the macro without exporting it, clients derive the PDA from this.
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+#[event]
+pub struct GlobalInitialized {
+    pub global: Pubkey,
+    pub authority: Pubkey,
+    pub settings: GlobalSettings,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BondingCurveCreated {
+    pub bonding_curve: Pubkey,
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub start_time: i64,
+    pub start_slot: u64,
+    pub end_time: Option<i64>,
+    pub sol_raise_target: u64,
+    pub hard_cap: bool,
+    pub market_cap_target_lamports: Option<u64>,
+    pub launch_config: Option<Pubkey>,
+    pub realm_pubkey: Pubkey,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct TokensPurchased {
     pub bonding_curve: Pubkey,
     pub buyer: Pubkey,
-    pub sol_amount: u64,
+    /// Total SOL spent by the buyer, fee included. This is the filled amount.
+    pub gross_sol_amount: u64,
+    /// Part of the gross amount paid to the fee receiver
+    pub fee_lamports: u64,
+    /// Fee rate of the launch phase the trade landed in
+    pub fee_bps: u64,
+    pub fee_receiver: Pubkey,
+    /// Part of the gross amount that went into the curve
+    pub net_sol_amount: u64,
     pub token_amount: u64,
-    pub price_per_token: f64,
+    /// SOL offered by the buyer, only `gross_sol_amount` of it is charged
+    pub requested_sol_amount: u64,
+    /// Part of the requested amount left with the buyer
+    pub unfilled_sol_amount: u64,
+    /// The buy was cut short by the hard cap or by the last tokens on the curve
+    pub partial_fill: bool,
+    /// Lamports per whole token, scaled by `BondingCurve::PRICE_SCALE`
+    pub price_per_token: u128,
+    /// Curve reserves after the trade
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
     pub timestamp: i64,
 }
 
@@ -15,8 +72,18 @@ pub struct TokensSold {
     pub bonding_curve: Pubkey,
     pub seller: Pubkey,
     pub token_amount: u64,
+    /// SOL taken out of the curve, the seller receives it minus the fee
     pub sol_amount: u64,
-    pub price_per_token: f64,
+    pub fee_lamports: u64,
+    pub fee_bps: u64,
+    pub fee_receiver: Pubkey,
+    /// Lamports per whole token, scaled by `BondingCurve::PRICE_SCALE`
+    pub price_per_token: u128,
+    /// Curve reserves after the trade
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
     pub timestamp: i64,
 }
 
@@ -27,4 +94,138 @@ pub struct TargetReached {
     pub timestamp: i64,
 }
 
-// We'll add more events for migration later
+/// Emitted by the buy that completes a curve, whatever triggered it
+#[event]
+pub struct CurveCompleted {
+    pub bonding_curve: Pubkey,
+    pub reason: CompletionReason,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityMigrated {
+    pub bonding_curve: Pubkey,
+    pub treasury: Pubkey,
+    pub liquidity_vault: Pubkey,
+    pub treasury_sol_amount: u64,
+    pub fee_receiver: Pubkey,
+    pub migrate_fee_amount: u64,
+    pub liquidity_sol_amount: u64,
+    pub liquidity_token_amount: u64,
+    pub treasury_token_amount: u64,
+    pub burned_token_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GlobalSettingsUpdated {
+    pub global: Pubkey,
+    pub authority: Pubkey,
+    pub old_settings: GlobalSettings,
+    pub new_settings: GlobalSettings,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub global: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferAccepted {
+    pub global: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub global: Pubkey,
+    pub authority: Pubkey,
+    pub cancelled_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RolesGranted {
+    pub member: Pubkey,
+    pub authority: Pubkey,
+    /// Bits added by this grant
+    pub granted: u8,
+    pub permissions: u8,
+    pub timestamp: i64,
+}
+
+/// `permissions` is zero once every role is revoked, the roles account is then closed
+#[event]
+pub struct RolesRevoked {
+    pub member: Pubkey,
+    pub authority: Pubkey,
+    pub revoked: u8,
+    pub permissions: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LaunchConfigCreated {
+    pub launch_config: Pubkey,
+    pub id: u64,
+    pub authority: Pubkey,
+    pub params: LaunchConfigParams,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LaunchConfigUpdated {
+    pub launch_config: Pubkey,
+    pub id: u64,
+    pub authority: Pubkey,
+    pub old_params: LaunchConfigParams,
+    pub new_params: LaunchConfigParams,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Skimmed {
+    pub bonding_curve: Pubkey,
+    pub recipient: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CurveRefunding {
+    pub bonding_curve: Pubkey,
+    pub end_time: i64,
+    pub real_sol_reserves: u64,
+    pub circulating_token_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensRefunded {
+    pub bonding_curve: Pubkey,
+    pub holder: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityWithdrawn {
+    pub bonding_curve: Pubkey,
+    pub authority: Pubkey,
+    pub recipient: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub timestamp: i64,
+}
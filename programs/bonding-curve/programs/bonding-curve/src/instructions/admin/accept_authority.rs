@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ContractError,
+    state::Global,
+    AuthorityTransferAccepted, // Event
+};
+
+/// Second step of handing over the global authority, signed by the proposed key
+/// so the authority can't move to a key nobody controls.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub new_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = global.pending_authority.is_some() @ ContractError::NoPendingAuthority,
+        constraint = global.pending_authority == Some(*new_authority.key) @ ContractError::InvalidPendingAuthority,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+}
+
+impl<'info> AcceptAuthority<'info> {
+    pub fn process(&mut self) -> Result<AuthorityTransferAccepted> {
+        let old_authority = self.global.global_authority;
+        self.global.global_authority = *self.new_authority.key;
+        self.global.pending_authority = None;
+
+        let event = AuthorityTransferAccepted {
+            global: self.global.key(),
+            old_authority,
+            new_authority: *self.new_authority.key,
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+        msg!("AcceptAuthority::process: done");
+        Ok(event)
+    }
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{ errors::ContractError, state::{ Global, Roles, Whitelist } };
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct AddToWhitelist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+    #[account(
+        seeds = [Roles::SEED_PREFIX.as_bytes(), authority.key().as_ref()],
+        bump = roles.bump
+    )]
+    /// Roles of `authority`, the global authority doesn't need one
+    pub roles: Option<Box<Account<'info, Roles>>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [Whitelist::SEED_PREFIX.as_bytes(), creator.as_ref()],
+        bump,
+        space = 8 + Whitelist::INIT_SPACE
+    )]
+    pub whitelist: Box<Account<'info, Whitelist>>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AddToWhitelist<'info> {
+    pub fn process(&mut self, creator: Pubkey, bumps: &AddToWhitelistBumps) -> Result<()> {
+        self.global.require_roles(
+            self.authority.key,
+            self.roles.as_ref().map(|roles| roles.permissions),
+            Roles::CONFIG_MANAGER
+        )?;
+        // A freshly created account is zeroed, anything else is already listed
+        require!(self.whitelist.creator == Pubkey::default(), ContractError::AddFailed);
+        self.whitelist.creator = creator;
+        self.whitelist.bump = bumps.whitelist;
+        msg!("AddToWhitelist::process: added {}", creator);
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ContractError,
+    state::Global,
+    AuthorityTransferCancelled, // Event
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelAuthorityTransfer<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = global.global_authority == *authority.key @ ContractError::InvalidGlobalAuthority,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+}
+
+impl<'info> CancelAuthorityTransfer<'info> {
+    pub fn process(&mut self) -> Result<AuthorityTransferCancelled> {
+        let cancelled_authority = self.global.pending_authority
+            .take()
+            .ok_or(ContractError::NoPendingAuthority)?;
+
+        let event = AuthorityTransferCancelled {
+            global: self.global.key(),
+            authority: *self.authority.key,
+            cancelled_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+        msg!("CancelAuthorityTransfer::process: cancelled {}", cancelled_authority);
+        Ok(event)
+    }
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ContractError,
+    state::{ Global, LaunchConfig, LaunchConfigParams, Roles },
+    LaunchConfigCreated, // Event
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateLaunchConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+    #[account(
+        seeds = [Roles::SEED_PREFIX.as_bytes(), authority.key().as_ref()],
+        bump = roles.bump
+    )]
+    /// Roles of `authority`, the global authority doesn't need one
+    pub roles: Option<Box<Account<'info, Roles>>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [LaunchConfig::SEED_PREFIX.as_bytes(), id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + LaunchConfig::INIT_SPACE
+    )]
+    pub launch_config: Box<Account<'info, LaunchConfig>>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateLaunchConfig<'info> {
+    pub fn process(
+        &mut self,
+        id: u64,
+        params: LaunchConfigParams,
+        bumps: &CreateLaunchConfigBumps
+    ) -> Result<LaunchConfigCreated> {
+        self.global.require_roles(
+            self.authority.key,
+            self.roles.as_ref().map(|roles| roles.permissions),
+            Roles::CONFIG_MANAGER
+        )?;
+        params.validate()?;
+        self.launch_config.set_inner(LaunchConfig {
+            id,
+            params: params.clone(),
+            bump: bumps.launch_config,
+        });
+
+        let event = LaunchConfigCreated {
+            launch_config: self.launch_config.key(),
+            id,
+            authority: *self.authority.key,
+            params,
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+        msg!("CreateLaunchConfig::process: created {}", id);
+        Ok(event)
+    }
+}
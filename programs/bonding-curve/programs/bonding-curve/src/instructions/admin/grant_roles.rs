@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ContractError,
+    state::{ Global, Roles },
+    RolesGranted, // Event
+};
+
+/// Adds `permissions` to the roles of `member`, only the global authority can hand out roles
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct GrantRoles<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = global.global_authority == *authority.key @ ContractError::InvalidGlobalAuthority,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [Roles::SEED_PREFIX.as_bytes(), member.as_ref()],
+        bump,
+        space = 8 + Roles::INIT_SPACE
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> GrantRoles<'info> {
+    pub fn process(
+        &mut self,
+        member: Pubkey,
+        permissions: u8,
+        bumps: &GrantRolesBumps
+    ) -> Result<RolesGranted> {
+        Roles::validate_permissions(permissions)?;
+        self.roles.member = member;
+        self.roles.permissions |= permissions;
+        self.roles.bump = bumps.roles;
+
+        let event = RolesGranted {
+            member,
+            authority: *self.authority.key,
+            granted: permissions,
+            permissions: self.roles.permissions,
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+        msg!("GrantRoles::process: {} has roles {:#05b}", member, self.roles.permissions);
+        Ok(event)
+    }
+}
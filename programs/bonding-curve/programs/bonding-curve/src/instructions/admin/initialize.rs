@@ -1,7 +1,12 @@
 use anchor_lang::prelude::*;
 
-use crate::{ errors::ContractError, state::{ Global, GlobalSettingsInput } };
+use crate::{
+    errors::ContractError,
+    state::{ FeeSchedule, Global, GlobalSettingsInput },
+    GlobalInitialized, // Event
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(mut)]
@@ -10,7 +15,7 @@ pub struct Initialize<'info> {
         init,
         seeds=[Global::SEED_PREFIX.as_bytes()],
         bump,
-        constraint = global.initialized != true @ ContractError::AlreadyInitialized,
+        constraint = !global.initialized @ ContractError::AlreadyInitialized,
         space= 8 + Global::INIT_SPACE,
         payer=admin,
     )]
@@ -19,12 +24,21 @@ pub struct Initialize<'info> {
 }
 
 impl<'info> Initialize<'info> {
-    pub fn process(&mut self, params: GlobalSettingsInput, bumps: &InitializeBumps) -> Result<()> {
+    pub fn process(&mut self, params: GlobalSettingsInput, bumps: &InitializeBumps) -> Result<GlobalInitialized> {
+        self.global.fee_schedule = FeeSchedule::default();
         self.global.update_settings(params);
         self.global.global_authority = *self.admin.key;
         self.global.initialized = true;
         self.global.bump = bumps.global;
-        require_gt!(self.global.mint_decimals, 0, ContractError::InvalidArgument);
-        Ok(())
+        self.global.validate()?;
+
+        let event = GlobalInitialized {
+            global: self.global.key(),
+            authority: *self.admin.key,
+            settings: self.global.settings(),
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+        msg!("Initialize::process: done");
+        Ok(event)
     }
 }
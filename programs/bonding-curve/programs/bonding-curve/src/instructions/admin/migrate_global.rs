@@ -0,0 +1,56 @@
+use anchor_lang::{ prelude::*, system_program::{ transfer, Transfer } };
+
+use crate::{ errors::ContractError, state::{ Global, LegacyGlobal } };
+
+/// One-time upgrade of global settings created by the original program release to
+/// the current layout. The global authority pays the rent of the extra bytes.
+#[derive(Accounts)]
+pub struct MigrateGlobal<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: still in the legacy layout, which `Global` can't deserialize.
+    /// Decoded by `LegacyGlobal::try_decode` and reallocated in `process`.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        bump
+    )]
+    pub global: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateGlobal<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let legacy = LegacyGlobal::try_decode(&self.global.try_borrow_data()?)?;
+        require_keys_eq!(
+            *self.authority.key,
+            legacy.global_authority,
+            ContractError::InvalidGlobalAuthority
+        );
+
+        let new_size = 8 + Global::INIT_SPACE;
+        let missing = Rent::get()?
+            .minimum_balance(new_size)
+            .saturating_sub(self.global.lamports());
+        if missing > 0 {
+            transfer(
+                CpiContext::new(self.system_program.to_account_info(), Transfer {
+                    from: self.authority.to_account_info(),
+                    to: self.global.to_account_info(),
+                }),
+                missing
+            )?;
+        }
+        self.global.realloc(new_size, false)?;
+
+        // Not validated, settings the current checks reject are fixed afterwards
+        // through `update_global_settings`
+        Global::from(legacy).try_serialize(&mut &mut self.global.try_borrow_mut_data()?[..])?;
+
+        msg!("MigrateGlobal::process: done");
+        Ok(())
+    }
+}
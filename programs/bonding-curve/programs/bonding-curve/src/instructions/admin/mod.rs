@@ -1,2 +1,24 @@
+mod accept_authority;
+mod add_to_whitelist;
+mod cancel_authority_transfer;
+mod create_launch_config;
+mod grant_roles;
 mod initialize;
+mod migrate_global;
+mod propose_authority;
+mod remove_from_whitelist;
+mod revoke_roles;
+mod update_global_settings;
+mod update_launch_config;
+pub use accept_authority::*;
+pub use add_to_whitelist::*;
+pub use cancel_authority_transfer::*;
+pub use create_launch_config::*;
+pub use grant_roles::*;
 pub use initialize::*;
+pub use migrate_global::*;
+pub use propose_authority::*;
+pub use remove_from_whitelist::*;
+pub use revoke_roles::*;
+pub use update_global_settings::*;
+pub use update_launch_config::*;
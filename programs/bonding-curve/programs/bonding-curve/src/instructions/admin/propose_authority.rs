@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ContractError,
+    state::Global,
+    AuthorityTransferProposed, // Event
+};
+
+/// First step of handing over the global authority. Proposing again replaces
+/// the pending authority.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = global.global_authority == *authority.key @ ContractError::InvalidGlobalAuthority,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+}
+
+impl<'info> ProposeAuthority<'info> {
+    pub fn process(&mut self, new_authority: Pubkey) -> Result<AuthorityTransferProposed> {
+        require!(new_authority != Pubkey::default(), ContractError::InvalidArgument);
+        self.global.pending_authority = Some(new_authority);
+
+        let event = AuthorityTransferProposed {
+            global: self.global.key(),
+            authority: *self.authority.key,
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+        msg!("ProposeAuthority::process: proposed {}", new_authority);
+        Ok(event)
+    }
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{ errors::ContractError, state::{ Global, Roles, Whitelist } };
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct RemoveFromWhitelist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+    #[account(
+        seeds = [Roles::SEED_PREFIX.as_bytes(), authority.key().as_ref()],
+        bump = roles.bump
+    )]
+    /// Roles of `authority`, the global authority doesn't need one
+    pub roles: Option<Box<Account<'info, Roles>>>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [Whitelist::SEED_PREFIX.as_bytes(), creator.as_ref()],
+        constraint = whitelist.creator == creator @ ContractError::RemoveFailed,
+        bump = whitelist.bump
+    )]
+    pub whitelist: Box<Account<'info, Whitelist>>,
+}
+
+impl<'info> RemoveFromWhitelist<'info> {
+    pub fn process(&mut self, creator: Pubkey) -> Result<()> {
+        self.global.require_roles(
+            self.authority.key,
+            self.roles.as_ref().map(|roles| roles.permissions),
+            Roles::CONFIG_MANAGER
+        )?;
+        msg!("RemoveFromWhitelist::process: removed {}", creator);
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ContractError,
+    state::{ Global, Roles },
+    RolesRevoked, // Event
+};
+
+/// Removes `permissions` from the roles of `member`, and closes the roles
+/// account once nothing is left
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct RevokeRoles<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        constraint = global.global_authority == *authority.key @ ContractError::InvalidGlobalAuthority,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+    #[account(
+        mut,
+        seeds = [Roles::SEED_PREFIX.as_bytes(), member.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+}
+
+impl<'info> RevokeRoles<'info> {
+    pub fn process(
+        &mut self,
+        member: Pubkey,
+        permissions: u8
+    ) -> Result<RolesRevoked> {
+        Roles::validate_permissions(permissions)?;
+        self.roles.permissions &= !permissions;
+        let remaining = self.roles.permissions;
+        if remaining == 0 {
+            self.roles.close(self.authority.to_account_info())?;
+        }
+
+        let event = RolesRevoked {
+            member,
+            authority: *self.authority.key,
+            revoked: permissions,
+            permissions: remaining,
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+        msg!("RevokeRoles::process: {} has roles {:#05b}", member, remaining);
+        Ok(event)
+    }
+}
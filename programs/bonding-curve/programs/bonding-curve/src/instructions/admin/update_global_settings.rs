@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ContractError,
+    state::{ Global, GlobalSettingsInput, Roles },
+    GlobalSettingsUpdated, // Event
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateGlobalSettings<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+    #[account(
+        seeds = [Roles::SEED_PREFIX.as_bytes(), authority.key().as_ref()],
+        bump = roles.bump
+    )]
+    /// Roles of `authority`, the global authority doesn't need one
+    pub roles: Option<Box<Account<'info, Roles>>>,
+}
+
+impl<'info> UpdateGlobalSettings<'info> {
+    pub fn process(
+        &mut self,
+        params: GlobalSettingsInput
+    ) -> Result<GlobalSettingsUpdated> {
+        // Every setting needs a role, so an empty update would pass for any role holder
+        require!(params.required_roles() != 0, ContractError::EmptySettingsUpdate);
+        self.global.require_roles(
+            self.authority.key,
+            self.roles.as_ref().map(|roles| roles.permissions),
+            params.required_roles()
+        )?;
+        let old_settings = self.global.settings();
+        self.global.update_settings(params);
+        self.global.validate()?;
+
+        let event = GlobalSettingsUpdated {
+            global: self.global.key(),
+            authority: *self.authority.key,
+            old_settings,
+            new_settings: self.global.settings(),
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+        msg!("UpdateGlobalSettings::process: done");
+        Ok(event)
+    }
+}
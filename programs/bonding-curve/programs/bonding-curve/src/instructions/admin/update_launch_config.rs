@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ContractError,
+    state::{ Global, LaunchConfig, LaunchConfigParams, Roles },
+    LaunchConfigUpdated, // Event
+};
+
+/// Replaces the parameters of a launch config. Curves already created from it
+/// keep the parameters they were created with.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateLaunchConfig<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+    #[account(
+        seeds = [Roles::SEED_PREFIX.as_bytes(), authority.key().as_ref()],
+        bump = roles.bump
+    )]
+    /// Roles of `authority`, the global authority doesn't need one
+    pub roles: Option<Box<Account<'info, Roles>>>,
+    #[account(
+        mut,
+        seeds = [LaunchConfig::SEED_PREFIX.as_bytes(), launch_config.id.to_le_bytes().as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Box<Account<'info, LaunchConfig>>,
+}
+
+impl<'info> UpdateLaunchConfig<'info> {
+    pub fn process(
+        &mut self,
+        params: LaunchConfigParams
+    ) -> Result<LaunchConfigUpdated> {
+        self.global.require_roles(
+            self.authority.key,
+            self.roles.as_ref().map(|roles| roles.permissions),
+            Roles::CONFIG_MANAGER
+        )?;
+        params.validate()?;
+        let old_params = std::mem::replace(&mut self.launch_config.params, params);
+
+        let event = LaunchConfigUpdated {
+            launch_config: self.launch_config.key(),
+            id: self.launch_config.id,
+            authority: *self.authority.key,
+            old_params,
+            new_params: self.launch_config.params.clone(),
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+        msg!("UpdateLaunchConfig::process: done");
+        Ok(event)
+    }
+}
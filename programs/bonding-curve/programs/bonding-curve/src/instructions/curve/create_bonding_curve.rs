@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{ prelude::*, system_program::{ transfer, Transfer } };
 use anchor_spl::{
     associated_token::AssociatedToken,
     metadata::{
@@ -17,15 +17,22 @@ use crate::{
     CreateBondingCurveParams,
     Global,
     IntoBondingCurveLockerCtx,
-    ProgramStatus,
+    LaunchConfig,
+    LaunchConfigParams,
+    ProgramAction,
+    Whitelist,
+    BondingCurveCreated, // Event
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CreateBondingCurve<'info> {
     #[account(
         init,
         payer = creator,
-        mint::decimals = global.mint_decimals,
+        mint::decimals = launch_config
+            .as_ref()
+            .map_or(global.mint_decimals, |launch_config| launch_config.params.mint_decimals),
         mint::authority = bonding_curve,
         mint::freeze_authority = bonding_curve
     )]
@@ -40,6 +47,12 @@ pub struct CreateBondingCurve<'info> {
         space = 8 + BondingCurve::INIT_SPACE
     )]
     pub bonding_curve: Box<Account<'info, BondingCurve>>,
+    #[account(
+        mut,
+        seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), bonding_curve.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
     #[account(
         init_if_needed,
         payer = creator,
@@ -49,11 +62,22 @@ pub struct CreateBondingCurve<'info> {
     bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         seeds = [Global::SEED_PREFIX.as_bytes()],
-        constraint = global.initialized == true @ ContractError::NotInitialized,
-        constraint = global.status == ProgramStatus::Running @ ContractError::ProgramNotRunning,
+        constraint = global.initialized @ ContractError::NotInitialized,
         bump,
     )]
     global: Box<Account<'info, Global>>,
+    #[account(
+        seeds = [Whitelist::SEED_PREFIX.as_bytes(), creator.key().as_ref()],
+        bump = whitelist.bump
+    )]
+    /// Only required while `global.whitelist_enabled` is set
+    pub whitelist: Option<Box<Account<'info, Whitelist>>>,
+    #[account(
+        seeds = [LaunchConfig::SEED_PREFIX.as_bytes(), launch_config.id.to_le_bytes().as_ref()],
+        bump = launch_config.bump
+    )]
+    /// Launch parameters, the global settings are used without one
+    pub launch_config: Option<Box<Account<'info, LaunchConfig>>>,
     #[account(mut)]
     ///CHECK: Using seed to validate metadata account
     metadata: UncheckedAccount<'info>,
@@ -76,16 +100,41 @@ impl<'info> IntoBondingCurveLockerCtx<'info> for CreateBondingCurve<'info> {
             bonding_curve_token_account: self.bonding_curve_token_account.clone(),
             token_program: self.token_program.clone(),
             global: self.global.clone(),
+            sol_vault: self.sol_vault.clone(),
+            system_program: self.system_program.clone(),
         }
     }
 }
 
 impl<'info> CreateBondingCurve<'info> {
     pub fn validate(&self, params: &CreateBondingCurveParams) -> Result<()> {
+        self.global.require_status(ProgramAction::CreateBondingCurve)?;
+        if self.global.whitelist_enabled {
+            require!(self.whitelist.is_some(), ContractError::NotWhiteList);
+        }
         let clock = Clock::get()?;
         if let Some(start_time) = params.start_time {
             require!(start_time >= clock.unix_timestamp, ContractError::InvalidStartTime);
         }
+        if let Some(start_slot) = params.start_slot {
+            require!(start_slot >= clock.slot, ContractError::InvalidStartTime);
+        }
+        if let Some(fee_schedule) = &params.fee_schedule {
+            fee_schedule.validate()?;
+        }
+        self.launch_config_params().require_sol_raise_target(params.sol_raise_target)?;
+        if let Some(end_time) = params.end_time {
+            let start_time = params.start_time.unwrap_or(clock.unix_timestamp);
+            require!(end_time > start_time, ContractError::InvalidEndTime);
+        }
+        if let Some(market_cap_target) = params.market_cap_target_lamports {
+            // Otherwise the very first buy would complete the curve
+            let starting_market_cap = self.bonding_curve.market_cap_lamports()?;
+            require!(
+                market_cap_target > starting_market_cap,
+                ContractError::InvalidMarketCapTarget
+            );
+        }
         // add more validations here
         Ok(())
     }
@@ -94,17 +143,23 @@ impl<'info> CreateBondingCurve<'info> {
         &mut self,
         params: CreateBondingCurveParams,
         bumps: &CreateBondingCurveBumps
-    ) -> Result<()> {
+    ) -> Result<BondingCurveCreated> {
         let clock = Clock::get()?;
+        let launch_params = self.launch_config_params();
         self.bonding_curve.update_from_params(
             self.mint.key(),
             *self.creator.key,
-            &self.global,
+            &launch_params,
             &params,
             &clock,
             bumps.bonding_curve
         );
+        self.bonding_curve.sol_vault_bump = Some(bumps.sol_vault);
+        self.bonding_curve.launch_config = self.launch_config
+            .as_ref()
+            .map(|launch_config| launch_config.key());
         self.validate(&params)?;
+        self.fund_sol_vault()?;
         let mint_k = self.mint.key();
         let mint_authority_signer = BondingCurve::get_signer(&bumps.bonding_curve, &mint_k);
         let mint_auth_signer_seeds = &[&mint_authority_signer[..]];
@@ -132,7 +187,53 @@ impl<'info> CreateBondingCurve<'info> {
         msg!("Lock ATA complete");
         msg!("Checking invariant");
         BondingCurve::invariant(locker)?;
+
+        let event = BondingCurveCreated {
+            bonding_curve: self.bonding_curve.key(),
+            mint: self.mint.key(),
+            creator: self.creator.key(),
+            name: params.name,
+            symbol: params.symbol,
+            uri: params.uri,
+            start_time: self.bonding_curve.start_time,
+            start_slot: self.bonding_curve.start_slot,
+            end_time: self.bonding_curve.end_time,
+            sol_raise_target: self.bonding_curve.sol_raise_target,
+            hard_cap: self.bonding_curve.hard_cap,
+            market_cap_target_lamports: self.bonding_curve.market_cap_target_lamports,
+            launch_config: self.bonding_curve.launch_config,
+            realm_pubkey: self.bonding_curve.realm_pubkey,
+            virtual_sol_reserves: self.bonding_curve.virtual_sol_reserves,
+            virtual_token_reserves: self.bonding_curve.virtual_token_reserves,
+            real_token_reserves: self.bonding_curve.real_token_reserves,
+            token_total_supply: self.bonding_curve.token_total_supply,
+            timestamp: clock.unix_timestamp,
+        };
         msg!("CreateBondingCurve::process: done");
+        Ok(event)
+    }
+
+    fn launch_config_params(&self) -> LaunchConfigParams {
+        match &self.launch_config {
+            Some(launch_config) => launch_config.params.clone(),
+            None => self.global.launch_config_params(),
+        }
+    }
+
+    /// The vault is a plain system account, so it has to start out rent exempt
+    fn fund_sol_vault(&self) -> Result<()> {
+        let rent_exemption_balance = Rent::get()?.minimum_balance(0);
+        let missing = rent_exemption_balance.saturating_sub(self.sol_vault.lamports());
+        if missing > 0 {
+            transfer(
+                CpiContext::new(self.system_program.to_account_info(), Transfer {
+                    from: self.creator.to_account_info(),
+                    to: self.sol_vault.to_account_info(),
+                }),
+                missing
+            )?;
+        }
+        msg!("CreateBondingCurve::fund_sol_vault: done");
         Ok(())
     }
 
@@ -0,0 +1,97 @@
+use anchor_lang::{ prelude::*, system_program::{ transfer, Transfer } };
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    errors::ContractError,
+    BondingCurve,
+    Global,
+    LegacyBondingCurve,
+    ProgramAction,
+};
+
+/// One-time move of the pool SOL of a curve created before the SOL vault existed.
+/// Permissionless, the payer covers the vault's rent exemption and the rent of the
+/// bytes the curve grows by.
+#[derive(Accounts)]
+pub struct MigrateSolVault<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: still in the legacy layout, which `BondingCurve` can't deserialize.
+    /// Decoded by `LegacyBondingCurve::try_decode` and reallocated in `process`.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
+        bump
+    )]
+    pub bonding_curve: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), bonding_curve.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateSolVault<'info> {
+    pub fn process(&mut self, bumps: &MigrateSolVaultBumps) -> Result<()> {
+        self.global.require_status(ProgramAction::MigrateSolVault)?;
+        let legacy = LegacyBondingCurve::try_decode(&self.bonding_curve.try_borrow_data()?)?;
+        require_keys_eq!(legacy.mint, self.mint.key(), ContractError::NotBondingCurveMint);
+        let rent = Rent::get()?;
+
+        // Fund the vault's rent exemption first so it never holds pool SOL below it
+        self.pay(&self.sol_vault, rent.minimum_balance(0).saturating_sub(self.sol_vault.lamports()))?;
+
+        // Everything above rent on the legacy sized account is pool SOL (plus any donations)
+        let pool_lamports = self.bonding_curve
+            .lamports()
+            .saturating_sub(rent.minimum_balance(LegacyBondingCurve::ACCOUNT_SIZE));
+        require!(pool_lamports >= legacy.real_sol_reserves, ContractError::BondingCurveInvariant);
+        // The bonding curve is program owned, so lamports are moved directly
+        self.bonding_curve.sub_lamports(pool_lamports)?;
+        self.sol_vault.add_lamports(pool_lamports)?;
+        msg!("Moved {} lamports to the SOL vault", pool_lamports);
+
+        // Grow the curve to the current layout, the payer covers the extra rent
+        let new_size = 8 + BondingCurve::INIT_SPACE;
+        self.pay(
+            &self.bonding_curve,
+            rent.minimum_balance(new_size).saturating_sub(self.bonding_curve.lamports())
+        )?;
+        self.bonding_curve.realloc(new_size, false)?;
+
+        let mut bonding_curve = legacy.into_bonding_curve(self.mint.decimals, &Clock::get()?);
+        bonding_curve.sol_vault_bump = Some(bumps.sol_vault);
+        bonding_curve.try_serialize(&mut &mut self.bonding_curve.try_borrow_mut_data()?[..])?;
+
+        msg!("MigrateSolVault::process: done");
+        Ok(())
+    }
+
+    fn pay(&self, to: &AccountInfo<'info>, lamports: u64) -> Result<()> {
+        if lamports > 0 {
+            transfer(
+                CpiContext::new(self.system_program.to_account_info(), Transfer {
+                    from: self.payer.to_account_info(),
+                    to: to.clone(),
+                }),
+                lamports
+            )?;
+        }
+        Ok(())
+    }
+}
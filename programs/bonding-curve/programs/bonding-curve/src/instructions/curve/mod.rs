@@ -1,4 +1,16 @@
 mod create_bonding_curve;
+mod migrate_liquidity;
+mod migrate_sol_vault;
+mod quote;
+mod refund;
+mod skim;
 mod swap;
+mod withdraw_liquidity;
 pub use create_bonding_curve::*;
-pub use swap::*;
\ No newline at end of file
+pub use migrate_liquidity::*;
+pub use migrate_sol_vault::*;
+pub use quote::*;
+pub use refund::*;
+pub use skim::*;
+pub use swap::*;
+pub use withdraw_liquidity::*;
\ No newline at end of file
@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    errors::ContractError,
+    math::{ self, Rounding },
+    BondingCurve,
+    Global,
+    LaunchConfig,
+    ProgramAction,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct QuoteParams {
+    /// Sell tokens for SOL, otherwise buy tokens with SOL
+    pub base_in: bool,
+    /// Input amount, or the exact output wanted when `exact_out` is set, same as `SwapParams`
+    pub amount: u64,
+    pub exact_out: bool,
+}
+
+/// What `swap` would do with the same params at the current slot
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct QuoteResult {
+    /// Lamports paid for buys (fee included), tokens sold for sells
+    pub in_amount: u64,
+    /// Tokens received for buys, lamports received for sells (fee deducted)
+    pub out_amount: u64,
+    pub fee_lamports: u64,
+    /// How far the average price of the trade is from the spot price before it
+    pub price_impact_bps: u64,
+    /// The trade would complete the curve
+    pub completes_curve: bool,
+}
+
+/// Read-only swap quote. The result is returned with `set_return_data`, so
+/// clients get it by simulating the instruction.
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        constraint = mint.key() == bonding_curve.mint @ ContractError::NotBondingCurveMint,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
+        constraint = !bonding_curve.complete @ ContractError::BondingCurveComplete,
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        seeds = [LaunchConfig::SEED_PREFIX.as_bytes(), launch_config.id.to_le_bytes().as_ref()],
+        bump = launch_config.bump
+    )]
+    /// Required for curves created from a launch config, same as `swap`
+    pub launch_config: Option<Box<Account<'info, LaunchConfig>>>,
+}
+
+impl<'info> Quote<'info> {
+    pub fn validate(&self, params: &QuoteParams, clock: &Clock) -> Result<()> {
+        self.global.require_status(ProgramAction::Swap)?;
+        self.bonding_curve.require_tradable(
+            clock,
+            self.launch_config.as_ref().map(|launch_config| launch_config.key())
+        )?;
+        require!(params.amount > 0, ContractError::MinSwap);
+        Ok(())
+    }
+
+    /// Runs the trade the way `Swap::process` does, on a copy of the curve
+    pub fn process(&self, params: QuoteParams) -> Result<QuoteResult> {
+        let clock = Clock::get()?;
+        self.validate(&params, &clock)?;
+        let QuoteParams { base_in, amount, exact_out } = params;
+
+        let bonding_curve: &BondingCurve = &self.bonding_curve;
+        let mut curve = bonding_curve.clone();
+        let spot_price = bonding_curve.price_per_token(
+            bonding_curve.virtual_sol_reserves,
+            bonding_curve.virtual_token_reserves
+        )?;
+
+        let (in_amount, out_amount, fee_lamports, trade_price) = if base_in {
+            let (sell_result, fee) = if exact_out {
+                let (gross_amount, fee) = bonding_curve.gross_for_net_after_fee(amount, &clock)?;
+                (curve.apply_sell_exact_out(gross_amount)?, fee)
+            } else {
+                let sell_result = curve.apply_sell(amount)?;
+                let fee = bonding_curve.calculate_fee(sell_result.sol_amount, &clock)?;
+                (sell_result, fee)
+            };
+            let out_amount = math::checked_sub(sell_result.sol_amount, fee)?;
+            (sell_result.token_amount, out_amount, fee, sell_result.price_per_token)
+        } else {
+            let buy_result = if exact_out {
+                curve.apply_buy_exact_out(amount)?
+            } else {
+                let fee = bonding_curve.calculate_fee(amount, &clock)?;
+                curve.apply_buy(math::checked_sub(amount, fee)?)?
+            };
+            let (gross_amount, fee) = if exact_out || buy_result.is_partial_fill() {
+                bonding_curve.gross_for_net_after_fee(buy_result.sol_amount, &clock)?
+            } else {
+                (amount, math::checked_sub(amount, buy_result.sol_amount)?)
+            };
+            (gross_amount, buy_result.token_amount, fee, buy_result.price_per_token)
+        };
+
+        let result = QuoteResult {
+            in_amount,
+            out_amount,
+            fee_lamports,
+            price_impact_bps: price_impact_bps(spot_price, trade_price)?,
+            completes_curve: curve.complete,
+        };
+        msg!("Quote: {:?}", result);
+        Ok(result)
+    }
+}
+
+/// Distance between `trade_price` and `spot_price` in bps of the spot price
+fn price_impact_bps(spot_price: u128, trade_price: u128) -> Result<u64> {
+    if spot_price == 0 {
+        return Ok(0);
+    }
+    let impact = math::mul_div_u128(
+        spot_price.abs_diff(trade_price),
+        math::BPS_DENOMINATOR as u128,
+        spot_price,
+        Rounding::Up
+    )?;
+    Ok(u64::try_from(impact).unwrap_or(u64::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn price_impact_is_relative_to_spot() {
+        assert_eq!(price_impact_bps(1_000, 1_000).unwrap(), 0);
+        assert_eq!(price_impact_bps(1_000, 1_100).unwrap(), 1_000);
+        assert_eq!(price_impact_bps(1_000, 900).unwrap(), 1_000);
+        // Rounded up so a tiny impact doesn't read as none
+        assert_eq!(price_impact_bps(3, 4).unwrap(), 3_334);
+        assert_eq!(price_impact_bps(0, 4).unwrap(), 0);
+    }
+}
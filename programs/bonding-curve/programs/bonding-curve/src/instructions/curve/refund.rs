@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint,
+    TokenAccount,
+    TokenInterface,
+    TransferChecked,
+    transfer_checked,
+};
+
+use crate::{
+    errors::ContractError,
+    BondingCurve,
+    BondingCurveLockerCtx,
+    Global,
+    IntoBondingCurveLockerCtx,
+    ProgramAction,
+    CurveRefunding, // Event
+    TokensRefunded, // Event
+};
+
+/// Returns tokens to a curve that missed its `end_time` for a pro-rata share of
+/// `real_sol_reserves`, without a fee
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        constraint = mint.key() == bonding_curve.mint @ ContractError::NotBondingCurveMint,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
+        constraint = bonding_curve.sol_vault_bump.is_some() @ ContractError::SolVaultNotMigrated,
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), bonding_curve.key().as_ref()],
+        bump = bonding_curve.sol_vault_bump.unwrap_or_default()
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = bonding_curve,
+    )]
+    pub bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> IntoBondingCurveLockerCtx<'info> for Refund<'info> {
+    fn into_bonding_curve_locker_ctx(
+        &self,
+        bonding_curve_bump: u8
+    ) -> BondingCurveLockerCtx<'info> {
+        BondingCurveLockerCtx {
+            bonding_curve_bump,
+            mint: self.mint.clone(),
+            bonding_curve: self.bonding_curve.clone(),
+            bonding_curve_token_account: self.bonding_curve_token_account.clone(),
+            token_program: self.token_program.clone(),
+            global: self.global.clone(),
+            sol_vault: self.sol_vault.clone(),
+            system_program: self.system_program.clone(),
+        }
+    }
+}
+
+impl<'info> Refund<'info> {
+    pub fn validate(&self, token_amount: u64, clock: &Clock) -> Result<()> {
+        self.global.require_status(ProgramAction::Refund)?;
+        require!(
+            self.bonding_curve.refunding || self.bonding_curve.is_expired(clock),
+            ContractError::CurveNotRefundable
+        );
+        require!(token_amount > 0, ContractError::MinSwap);
+        require!(
+            self.user_token_account.amount >= token_amount,
+            ContractError::InsufficientUserTokens
+        );
+        Ok(())
+    }
+
+    /// Returns `CurveRefunding` for the first refund of the curve, and the refund itself
+    pub fn process(
+        &mut self,
+        token_amount: u64
+    ) -> Result<(Option<CurveRefunding>, TokensRefunded)> {
+        let clock = Clock::get()?;
+        self.validate(token_amount, &clock)?;
+
+        // The first refund after the deadline moves the curve into refunding
+        let mut refunding = None;
+        if !self.bonding_curve.refunding {
+            self.bonding_curve.refunding = true;
+            refunding = Some(CurveRefunding {
+                bonding_curve: self.bonding_curve.key(),
+                end_time: self.bonding_curve.end_time.unwrap_or_default(),
+                real_sol_reserves: self.bonding_curve.real_sol_reserves,
+                circulating_token_amount: self.bonding_curve
+                    .circulating_token_amount()
+                    .ok_or(ContractError::ArithmeticError)?,
+                timestamp: clock.unix_timestamp,
+            });
+            msg!("Bonding curve is now refunding");
+        }
+
+        let sol_amount = self.bonding_curve.apply_refund(token_amount)?;
+
+        let locker = self.into_bonding_curve_locker_ctx(self.bonding_curve.bump);
+        locker.unlock_ata()?;
+        transfer_checked(
+            CpiContext::new(self.token_program.to_account_info(), TransferChecked {
+                from: self.user_token_account.to_account_info(),
+                authority: self.user.to_account_info(),
+                to: self.bonding_curve_token_account.to_account_info(),
+                mint: self.mint.to_account_info(),
+            }),
+            token_amount,
+            self.mint.decimals
+        )?;
+        locker.lock_ata()?;
+        msg!("Token transfer complete");
+
+        locker.withdraw_sol(&self.user.to_account_info(), sol_amount)?;
+        msg!("SOL transfer complete");
+
+        BondingCurve::invariant(&mut self.into_bonding_curve_locker_ctx(self.bonding_curve.bump))?;
+
+        let refunded = TokensRefunded {
+            bonding_curve: self.bonding_curve.key(),
+            holder: self.user.key(),
+            token_amount,
+            sol_amount,
+            timestamp: clock.unix_timestamp,
+        };
+
+        msg!("Refund::process: done");
+        Ok((refunding, refunded))
+    }
+}
@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked },
+};
+
+use crate::{
+    errors::ContractError,
+    BondingCurve,
+    BondingCurveLockerCtx,
+    Global,
+    IntoBondingCurveLockerCtx,
+    ProgramAction,
+    math,
+    Skimmed, // Event
+};
+
+/// Permissionless: sweeps lamports and tokens sent to the curve on top of its
+/// tracked reserves, so donations can't be used to grief the invariant
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Skim<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        constraint = mint.key() == bonding_curve.mint @ ContractError::NotBondingCurveMint,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
+        constraint = bonding_curve.sol_vault_bump.is_some() @ ContractError::SolVaultNotMigrated,
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), bonding_curve.key().as_ref()],
+        bump = bonding_curve.sol_vault_bump.unwrap_or_default()
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = bonding_curve,
+    )]
+    pub bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    /// CHECK: fee receiver or DAO treasury, asserted in validation function
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = recipient
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> IntoBondingCurveLockerCtx<'info> for Skim<'info> {
+    fn into_bonding_curve_locker_ctx(
+        &self,
+        bonding_curve_bump: u8
+    ) -> BondingCurveLockerCtx<'info> {
+        BondingCurveLockerCtx {
+            bonding_curve_bump,
+            mint: self.mint.clone(),
+            bonding_curve: self.bonding_curve.clone(),
+            bonding_curve_token_account: self.bonding_curve_token_account.clone(),
+            token_program: self.token_program.clone(),
+            global: self.global.clone(),
+            sol_vault: self.sol_vault.clone(),
+            system_program: self.system_program.clone(),
+        }
+    }
+}
+
+impl<'info> Skim<'info> {
+    pub fn validate(&self) -> Result<()> {
+        self.global.require_status(ProgramAction::Skim)?;
+        require!(
+            *self.recipient.key == self.global.fee_receiver ||
+                *self.recipient.key == self.bonding_curve.realm_pubkey,
+            ContractError::InvalidSkimRecipient
+        );
+        Ok(())
+    }
+
+    pub fn process(&mut self) -> Result<Skimmed> {
+        self.validate()?;
+        let clock = Clock::get()?;
+
+        let rent = Rent::get()?;
+        let (vault_sol_amount, token_amount) = self.bonding_curve.surplus(
+            self.sol_vault.lamports(),
+            rent.minimum_balance(0),
+            self.bonding_curve_token_account.amount
+        )?;
+        // Pool SOL lives in the vault, anything above rent on the state account was sent there directly
+        let curve_sol_amount = self.bonding_curve
+            .get_lamports()
+            .saturating_sub(rent.minimum_balance(8 + BondingCurve::INIT_SPACE));
+        let sol_amount = math::checked_add(vault_sol_amount, curve_sol_amount)?;
+        msg!("Skim: {} lamports, {} tokens", sol_amount, token_amount);
+        require!(sol_amount > 0 || token_amount > 0, ContractError::NothingToSkim);
+
+        let locker = self.into_bonding_curve_locker_ctx(self.bonding_curve.bump);
+        locker.withdraw_sol(&self.recipient, vault_sol_amount)?;
+        if curve_sol_amount > 0 {
+            // The bonding curve is program owned, so lamports are moved directly
+            self.bonding_curve.sub_lamports(curve_sol_amount)?;
+            self.recipient.add_lamports(curve_sol_amount)?;
+        }
+
+        if token_amount > 0 {
+            locker.unlock_ata()?;
+
+            let signer = BondingCurve::get_signer(&self.bonding_curve.bump, &self.bonding_curve.mint);
+            let signer_seeds = &[&signer[..]];
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    TransferChecked {
+                        from: self.bonding_curve_token_account.to_account_info(),
+                        authority: self.bonding_curve.to_account_info(),
+                        to: self.recipient_token_account.to_account_info(),
+                        mint: self.mint.to_account_info(),
+                    },
+                    signer_seeds
+                ),
+                token_amount,
+                self.mint.decimals
+            )?;
+            locker.lock_ata()?;
+        }
+
+        BondingCurve::invariant(&mut self.into_bonding_curve_locker_ctx(self.bonding_curve.bump))?;
+
+        let event = Skimmed {
+            bonding_curve: self.bonding_curve.key(),
+            recipient: self.recipient.key(),
+            sol_amount,
+            token_amount,
+            timestamp: clock.unix_timestamp,
+        };
+
+        msg!("Skim::process: done");
+        Ok(event)
+    }
+}
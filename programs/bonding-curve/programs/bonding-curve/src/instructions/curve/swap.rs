@@ -1,4 +1,4 @@
-use anchor_lang::{ prelude::*, solana_program::{ self, system_instruction } };
+use anchor_lang::{ prelude::*, system_program::{ transfer, Transfer } };
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{ Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked },
@@ -11,7 +11,11 @@ use crate::{
     BuyResult,
     Global,
     IntoBondingCurveLockerCtx,
+    LaunchConfig,
+    ProgramAction,
     SellResult,
+    math,
+    CurveCompleted, // Event
     TokensPurchased, // Event
     TokensSold, // Event
 };
@@ -19,17 +23,32 @@ use crate::{
 #[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
 pub struct SwapParams {
     pub base_in: bool,
+    /// Input amount, or the exact output wanted when `exact_out` is set
     pub amount: u64,
+    /// Slippage bound for exact input swaps
     pub min_out_amount: u64,
+    /// Buy exactly `amount` tokens, or sell for exactly `amount` lamports
+    pub exact_out: bool,
+    /// Slippage bound for exact output swaps, in lamports for buys (fee included)
+    /// and tokens for sells
+    pub max_in_amount: u64,
 }
 
+/// Events of a swap, emitted by the instruction handler. A buy also reports
+/// `CurveCompleted` when it completes the curve
+pub enum SwapEvents {
+    Buy(TokensPurchased, Option<CurveCompleted>),
+    Sell(TokensSold),
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Swap<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     #[account(
         seeds=[Global::SEED_PREFIX.as_bytes()],
-        constraint=global.initialized == true @ ContractError::NotInitialized,
+        constraint = global.initialized @ ContractError::NotInitialized,
         bump = global.bump
     )]
     pub global: Box<Account<'info, Global>>,
@@ -44,11 +63,26 @@ pub struct Swap<'info> {
         mut,
         seeds=[BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
         constraint = bonding_curve.mint == *mint.to_account_info().key @ ContractError::NotBondingCurveMint,
-        constraint = bonding_curve.complete == false @ ContractError::BondingCurveComplete,
+        constraint = !bonding_curve.complete @ ContractError::BondingCurveComplete,
+        constraint = bonding_curve.sol_vault_bump.is_some() @ ContractError::SolVaultNotMigrated,
         bump = bonding_curve.bump
     )]
     pub bonding_curve: Box<Account<'info, BondingCurve>>,
 
+    #[account(
+        seeds = [LaunchConfig::SEED_PREFIX.as_bytes(), launch_config.id.to_le_bytes().as_ref()],
+        bump = launch_config.bump
+    )]
+    /// Required for curves created from a launch config
+    pub launch_config: Option<Box<Account<'info, LaunchConfig>>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), bonding_curve.key().as_ref()],
+        bump = bonding_curve.sol_vault_bump.unwrap_or_default()
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
     #[account(
         mut,
         associated_token::mint = mint,
@@ -83,39 +117,51 @@ impl<'info> IntoBondingCurveLockerCtx<'info> for Swap<'info> {
             bonding_curve: self.bonding_curve.clone(),
             bonding_curve_token_account: self.bonding_curve_token_account.clone(),
             token_program: self.token_program.clone(),
+            sol_vault: self.sol_vault.clone(),
+            system_program: self.system_program.clone(),
         }
     }
 }
 
 impl<'info> Swap<'info> {
     pub fn validate(&self, params: &SwapParams) -> Result<()> {
-        let SwapParams { base_in, amount, min_out_amount: _ } = params;
-        let clock = Clock::get()?;
-        require!(self.bonding_curve.is_started(&clock), ContractError::CurveNotStarted);
+        let SwapParams { base_in, amount, .. } = params;
+        self.global.require_status(ProgramAction::Swap)?;
+        self.bonding_curve.require_tradable(
+            &Clock::get()?,
+            self.launch_config.as_ref().map(|launch_config| launch_config.key())
+        )?;
         require!(*amount > 0, ContractError::MinSwap);
         require!(
             self.fee_receiver.key() == self.global.fee_receiver.key(),
             ContractError::InvalidFeeReceiver
         );
         if !*base_in && self.bonding_curve.sol_raise_target > 0 {
-            if self.bonding_curve.sol_raise_target >= self.bonding_curve.sol_raise_target {
+            if self.bonding_curve.real_sol_reserves >= self.bonding_curve.sol_raise_target {
                 // will decide what's the best way to handle this
                 msg!("Target SOL reached. Maybe migrate now!?");
             }
 
-            if self.bonding_curve.real_sol_reserves + *amount > self.bonding_curve.sol_raise_target {
+            let new_sol_reserves = math::checked_add(
+                self.bonding_curve.real_sol_reserves,
+                *amount
+            )?;
+            if new_sol_reserves > self.bonding_curve.sol_raise_target {
                 msg!("Target SOL will be reached congrats!");
             }
         }
         Ok(())
     }
-    pub fn process(&mut self, params: SwapParams) -> Result<()> {
-        let SwapParams { base_in, amount, min_out_amount } = params;
+    pub fn process(&mut self, params: SwapParams) -> Result<SwapEvents> {
+        self.validate(&params)?;
+        let SwapParams { base_in, amount, min_out_amount, exact_out, max_in_amount } = params;
         msg!(
-            "Swap started. BaseIn: {}, AmountIn: {}, MinOutAmount: {}",
+            "Swap started. BaseIn: {}, Amount: {}, MinOutAmount: {}, ExactOut: {}, MaxInAmount: {}",
             base_in,
             amount,
-            min_out_amount
+            min_out_amount,
+            exact_out,
+            max_in_amount
         );
 
         let bonding_curve = self.bonding_curve.clone();
@@ -126,24 +172,21 @@ impl<'info> Swap<'info> {
         let token_amount: u64;
         let fee_lamports: u64;
         let clock = Clock::get()?;
+        let fee_bps = bonding_curve.fee_bps(&clock)?;
+        let events: SwapEvents;
 
         if base_in {
             // Sell token for SOL
-            require!(
-                self.user_token_account.amount >= amount,
-                ContractError::InsufficientUserTokens
-            );
-
             // Add check to verify bonding curve has enough SOL
             let bonding_curve_sol = self.bonding_curve.real_sol_reserves;
             msg!("Bonding curve SOL reserves: {}", bonding_curve_sol);
 
-            let sell_result = match self.bonding_curve.apply_sell(amount) {
-                Some(result) => result,
-                None => {
-                    msg!("Sell failed - likely insufficient SOL in bonding curve");
-                    return Err(ContractError::SellFailed.into());
-                }
+            let (sell_result, min_out_amount, exact_out_fee) = if exact_out {
+                // The seller receives exactly `amount`, the fee comes on top
+                let (gross_amount, fee) = bonding_curve.gross_for_net_after_fee(amount, &clock)?;
+                (self.bonding_curve.apply_sell_exact_out(gross_amount)?, amount, Some(fee))
+            } else {
+                (self.bonding_curve.apply_sell(amount)?, min_out_amount, None)
             };
 
             msg!("SellResult: {:#?}", sell_result);
@@ -151,45 +194,108 @@ impl<'info> Swap<'info> {
             sol_amount = sell_result.sol_amount;
             token_amount = sell_result.token_amount;
 
-            fee_lamports = bonding_curve.calculate_fee(sol_amount, clock.unix_timestamp)?;
+            require!(
+                self.user_token_account.amount >= token_amount,
+                ContractError::InsufficientUserTokens
+            );
+            if exact_out {
+                require!(token_amount <= max_in_amount, ContractError::SlippageExceeded);
+            }
+            fee_lamports = match exact_out_fee {
+                Some(fee) => fee,
+                None => bonding_curve.calculate_fee(sol_amount, &clock)?,
+            };
             msg!("Fee: {} SOL", fee_lamports);
             self.complete_sell(sell_result.clone(), min_out_amount, fee_lamports)?;
 
-            // Emit event with the actual price
-            emit!(TokensSold {
+            events = SwapEvents::Sell(TokensSold {
                 bonding_curve: self.bonding_curve.key(),
                 seller: self.user.key(),
                 token_amount,
                 sol_amount,
+                fee_lamports,
+                fee_bps,
+                fee_receiver: self.fee_receiver.key(),
                 price_per_token: sell_result.price_per_token,
+                virtual_sol_reserves: self.bonding_curve.virtual_sol_reserves,
+                virtual_token_reserves: self.bonding_curve.virtual_token_reserves,
+                real_sol_reserves: self.bonding_curve.real_sol_reserves,
+                real_token_reserves: self.bonding_curve.real_token_reserves,
                 timestamp: clock.unix_timestamp,
             });
         } else {
             // Buy token with SOL
-            let buy_result = self.bonding_curve.apply_buy(amount).ok_or(ContractError::BuyFailed)?;
+            let (buy_result, min_out_amount) = if exact_out {
+                (self.bonding_curve.apply_buy_exact_out(amount)?, amount)
+            } else {
+                // `amount` is the total spend, the fee comes out of it before quoting
+                let fee = bonding_curve.calculate_fee(amount, &clock)?;
+                let net_amount = math::checked_sub(amount, fee)?;
+                (self.bonding_curve.apply_buy(net_amount)?, min_out_amount)
+            };
 
             sol_amount = buy_result.sol_amount;
             token_amount = buy_result.token_amount;
 
-            fee_lamports = bonding_curve.calculate_fee(sol_amount, clock.unix_timestamp)?;
+            // Gross spend that covers the curve cost and the fee on it
+            let (gross_sol_amount, fee) = if exact_out || buy_result.is_partial_fill() {
+                bonding_curve.gross_for_net_after_fee(sol_amount, &clock)?
+            } else {
+                (amount, math::checked_sub(amount, sol_amount)?)
+            };
+            fee_lamports = fee;
+            if exact_out {
+                require!(gross_sol_amount <= max_in_amount, ContractError::SlippageExceeded);
+            }
             self.complete_buy(buy_result.clone(), min_out_amount, fee_lamports)?;
+            let requested_sol_amount = if exact_out { gross_sol_amount } else { amount };
+            if buy_result.is_partial_fill() {
+                msg!(
+                    "Partial fill: charged {} of {} lamports",
+                    gross_sol_amount,
+                    requested_sol_amount
+                );
+            }
 
-            // Emit simplified event to save compute units
-            emit!(TokensPurchased {
+            let purchased = TokensPurchased {
                 bonding_curve: self.bonding_curve.key(),
                 buyer: self.user.key(),
-                sol_amount,
+                gross_sol_amount,
+                fee_lamports,
+                fee_bps,
+                fee_receiver: self.fee_receiver.key(),
+                net_sol_amount: sol_amount,
                 token_amount,
+                requested_sol_amount,
+                unfilled_sol_amount: requested_sol_amount.saturating_sub(gross_sol_amount),
+                partial_fill: buy_result.is_partial_fill(),
                 price_per_token: buy_result.price_per_token,
+                virtual_sol_reserves: self.bonding_curve.virtual_sol_reserves,
+                virtual_token_reserves: self.bonding_curve.virtual_token_reserves,
+                real_sol_reserves: self.bonding_curve.real_sol_reserves,
+                real_token_reserves: self.bonding_curve.real_token_reserves,
+                timestamp: clock.unix_timestamp,
+            };
+
+            // Swaps only run on incomplete curves, so this buy completed it.
+            // `TargetReached` comes with the migration, once the raise is final
+            let completed = self.bonding_curve.completion_reason.map(|reason| CurveCompleted {
+                bonding_curve: self.bonding_curve.key(),
+                reason,
+                real_sol_reserves: self.bonding_curve.real_sol_reserves,
+                real_token_reserves: self.bonding_curve.real_token_reserves,
+                virtual_sol_reserves: self.bonding_curve.virtual_sol_reserves,
+                virtual_token_reserves: self.bonding_curve.virtual_token_reserves,
                 timestamp: clock.unix_timestamp,
             });
+            events = SwapEvents::Buy(purchased, completed);
         }
 
         // Optimize invariant check for production
         BondingCurve::invariant(&mut self.into_bonding_curve_locker_ctx(self.bonding_curve.bump))?;
         msg!("{:#?}", bonding_curve);
 
-        Ok(())
+        Ok(events)
     }
 
     fn complete_buy(
@@ -198,12 +304,9 @@ impl<'info> Swap<'info> {
         min_out_amount: u64,
         fee_lamports: u64
     ) -> Result<()> {
-        let buy_token_with_fee = buy_result.token_amount + fee_lamports;
+        let sol_with_fee = math::checked_add(buy_result.sol_amount, fee_lamports)?;
         require!(buy_result.token_amount >= min_out_amount, ContractError::SlippageExceeded);
-        require!(
-            self.user.get_lamports() >= buy_token_with_fee,
-            ContractError::InsufficientUserSOL
-        );
+        require!(self.user.get_lamports() >= sol_with_fee, ContractError::InsufficientUserSOL);
         // Transfer tokens to user
         let cpi_accounts = TransferChecked {
             from: self.bonding_curve_token_account.to_account_info(),
@@ -226,37 +329,23 @@ impl<'info> Swap<'info> {
         locker.lock_ata()?;
         msg!("Token transfer complete");
 
-        // Transfer entire SOL amount to bonding curve (no split during active phase)
-        let transfer_instruction = system_instruction::transfer(
-            self.user.key,
-            &self.bonding_curve.key(),
+        // Transfer entire SOL amount to the vault (no split during active phase)
+        transfer(
+            CpiContext::new(self.system_program.to_account_info(), Transfer {
+                from: self.user.to_account_info(),
+                to: self.sol_vault.to_account_info(),
+            }),
             buy_result.sol_amount
-        );
-        solana_program::program::invoke_signed(
-            &transfer_instruction,
-            &[
-                self.user.to_account_info(),
-                self.bonding_curve.to_account_info(),
-                self.system_program.to_account_info(),
-            ],
-            &[]
         )?;
         msg!("SOL transfer complete");
 
         // Transfer fee to fee receiver
-        let fee_transfer_ix = system_instruction::transfer(
-            self.user.key,
-            &self.fee_receiver.key(),
+        transfer(
+            CpiContext::new(self.system_program.to_account_info(), Transfer {
+                from: self.user.to_account_info(),
+                to: self.fee_receiver.to_account_info(),
+            }),
             fee_lamports
-        );
-        solana_program::program::invoke_signed(
-            &fee_transfer_ix,
-            &[
-                self.user.to_account_info(),
-                self.fee_receiver.clone(),
-                self.system_program.to_account_info(),
-            ],
-            &[]
         )?;
         msg!("Fee transfer complete");
         Ok(())
@@ -269,7 +358,7 @@ impl<'info> Swap<'info> {
         fee_lamports: u64
     ) -> Result<()> {
         // Sell tokens
-        let sell_amount_minus_fee = sell_result.sol_amount - fee_lamports;
+        let sell_amount_minus_fee = math::checked_sub(sell_result.sol_amount, fee_lamports)?;
         require!(sell_amount_minus_fee >= min_out_amount, ContractError::SlippageExceeded);
         let cpi_accounts = TransferChecked {
             from: self.user_token_account.to_account_info(),
@@ -287,12 +376,10 @@ impl<'info> Swap<'info> {
         msg!("Token transfer complete");
 
         // Transfer SOL to user
-        self.bonding_curve.sub_lamports(sell_amount_minus_fee).unwrap();
-        self.user.add_lamports(sell_amount_minus_fee).unwrap();
+        locker.withdraw_sol(&self.user.to_account_info(), sell_amount_minus_fee)?;
         msg!("SOL transfer complete");
 
-        self.bonding_curve.sub_lamports(fee_lamports).unwrap();
-        self.fee_receiver.add_lamports(fee_lamports).unwrap();
+        locker.withdraw_sol(&self.fee_receiver, fee_lamports)?;
         msg!("Fee transfer complete");
         Ok(())
     }
@@ -0,0 +1,141 @@
+use anchor_lang::{ prelude::*, system_program::{ transfer, Transfer } };
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked },
+};
+
+use crate::{
+    errors::ContractError,
+    BondingCurve,
+    Global,
+    LiquidityWithdrawn, // Event
+};
+
+/// Releases the SOL and tokens `migrate_liquidity` escrowed in the liquidity vault
+/// to an account of the global authority's choosing, which seeds the AMM pool
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawLiquidity<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [Global::SEED_PREFIX.as_bytes()],
+        constraint = global.initialized @ ContractError::NotInitialized,
+        bump = global.bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        constraint = mint.key() == bonding_curve.mint @ ContractError::NotBondingCurveMint,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), mint.to_account_info().key.as_ref()],
+        constraint = bonding_curve.migrated @ ContractError::BondingCurveNotMigrated,
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::LIQUIDITY_VAULT_SEED_PREFIX.as_bytes(), bonding_curve.key().as_ref()],
+        bump
+    )]
+    pub liquidity_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = liquidity_vault,
+    )]
+    pub liquidity_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    /// CHECK: any account, picked by the global authority
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = recipient
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> WithdrawLiquidity<'info> {
+    pub fn validate(&self) -> Result<()> {
+        require_keys_eq!(
+            *self.authority.key,
+            self.global.global_authority,
+            ContractError::InvalidGlobalAuthority
+        );
+        Ok(())
+    }
+
+    pub fn process(&mut self, bumps: &WithdrawLiquidityBumps) -> Result<LiquidityWithdrawn> {
+        self.validate()?;
+
+        // The vault is emptied, its rent exemption goes along with the pool SOL
+        let sol_amount = self.liquidity_vault.lamports();
+        let token_amount = self.liquidity_token_account.amount;
+        msg!("WithdrawLiquidity: {} lamports, {} tokens", sol_amount, token_amount);
+        require!(sol_amount > 0 || token_amount > 0, ContractError::NothingToWithdraw);
+
+        let bonding_curve_key = self.bonding_curve.key();
+        let signer = BondingCurve::get_liquidity_vault_signer(
+            &bonding_curve_key,
+            &bumps.liquidity_vault
+        );
+        let signer_seeds = &[&signer[..]];
+
+        if token_amount > 0 {
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    TransferChecked {
+                        from: self.liquidity_token_account.to_account_info(),
+                        authority: self.liquidity_vault.to_account_info(),
+                        to: self.recipient_token_account.to_account_info(),
+                        mint: self.mint.to_account_info(),
+                    },
+                    signer_seeds
+                ),
+                token_amount,
+                self.mint.decimals
+            )?;
+        }
+
+        if sol_amount > 0 {
+            transfer(
+                CpiContext::new_with_signer(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.liquidity_vault.to_account_info(),
+                        to: self.recipient.to_account_info(),
+                    },
+                    signer_seeds
+                ),
+                sol_amount
+            )?;
+        }
+
+        let event = LiquidityWithdrawn {
+            bonding_curve: bonding_curve_key,
+            authority: *self.authority.key,
+            recipient: self.recipient.key(),
+            sol_amount,
+            token_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+
+        msg!("WithdrawLiquidity::process: done");
+        Ok(event)
+    }
+}
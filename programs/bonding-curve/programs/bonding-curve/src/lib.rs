@@ -3,8 +3,10 @@
 declare_id!("C2LfjaKea6KJ15zXDzxghTSErN6xEqUnHzpg2Vrpdjnu");
 mod instructions;
 mod state;
-mod errors;
+pub mod errors;
 mod events; // Add the events module
+pub mod math;
+pub mod curve_math;
 
 pub use instructions::*;
 pub use state::*;
@@ -15,19 +17,137 @@ pub mod bonding_curve {
     use super::*;
 
     pub fn initialize(ctx: Context<Initialize>, params: GlobalSettingsInput) -> Result<()> {
-        ctx.accounts.process(params, &ctx.bumps)
+        let event = ctx.accounts.process(params, &ctx.bumps)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn migrate_global(ctx: Context<MigrateGlobal>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn update_global_settings(
+        ctx: Context<UpdateGlobalSettings>,
+        params: GlobalSettingsInput
+    ) -> Result<()> {
+        let event = ctx.accounts.process(params)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        let event = ctx.accounts.process(new_authority)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let event = ctx.accounts.process()?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
+        let event = ctx.accounts.process()?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn grant_roles(ctx: Context<GrantRoles>, member: Pubkey, permissions: u8) -> Result<()> {
+        let event = ctx.accounts.process(member, permissions, &ctx.bumps)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn revoke_roles(ctx: Context<RevokeRoles>, member: Pubkey, permissions: u8) -> Result<()> {
+        let event = ctx.accounts.process(member, permissions)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn add_to_whitelist(ctx: Context<AddToWhitelist>, creator: Pubkey) -> Result<()> {
+        ctx.accounts.process(creator, &ctx.bumps)
+    }
+
+    pub fn remove_from_whitelist(ctx: Context<RemoveFromWhitelist>, creator: Pubkey) -> Result<()> {
+        ctx.accounts.process(creator)
+    }
+
+    pub fn create_launch_config(
+        ctx: Context<CreateLaunchConfig>,
+        id: u64,
+        params: LaunchConfigParams
+    ) -> Result<()> {
+        let event = ctx.accounts.process(id, params, &ctx.bumps)?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn update_launch_config(
+        ctx: Context<UpdateLaunchConfig>,
+        params: LaunchConfigParams
+    ) -> Result<()> {
+        let event = ctx.accounts.process(params)?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     pub fn create_bonding_curve(
         ctx: Context<CreateBondingCurve>,
         params: CreateBondingCurveParams
     ) -> Result<()> {
-        ctx.accounts.process(params, &ctx.bumps)
+        let event = ctx.accounts.process(params, &ctx.bumps)?;
+        emit_cpi!(event);
+        Ok(())
     }
 
     pub fn swap(ctx: Context<Swap>, params: SwapParams) -> Result<()> {
+        match ctx.accounts.process(params)? {
+            SwapEvents::Buy(purchased, completed) => {
+                emit_cpi!(purchased);
+                if let Some(completed) = completed {
+                    emit_cpi!(completed);
+                }
+            }
+            SwapEvents::Sell(sold) => emit_cpi!(sold),
+        }
+        Ok(())
+    }
+
+    /// Quotes a swap without executing it, the result comes back as return data
+    pub fn quote(ctx: Context<Quote>, params: QuoteParams) -> Result<QuoteResult> {
         ctx.accounts.process(params)
     }
 
-    // Migration functionality will be added later
+    pub fn migrate_liquidity(ctx: Context<MigrateLiquidity>) -> Result<()> {
+        let (target_reached, migrated) = ctx.accounts.process()?;
+        emit_cpi!(target_reached);
+        emit_cpi!(migrated);
+        Ok(())
+    }
+
+    pub fn migrate_sol_vault(ctx: Context<MigrateSolVault>) -> Result<()> {
+        ctx.accounts.process(&ctx.bumps)
+    }
+
+    pub fn refund(ctx: Context<Refund>, token_amount: u64) -> Result<()> {
+        let (refunding, refunded) = ctx.accounts.process(token_amount)?;
+        if let Some(refunding) = refunding {
+            emit_cpi!(refunding);
+        }
+        emit_cpi!(refunded);
+        Ok(())
+    }
+
+    pub fn skim(ctx: Context<Skim>) -> Result<()> {
+        let event = ctx.accounts.process()?;
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>) -> Result<()> {
+        let event = ctx.accounts.process(&ctx.bumps)?;
+        emit_cpi!(event);
+        Ok(())
+    }
 }
@@ -0,0 +1,82 @@
+//! Checked arithmetic shared by the curve, fee and invariant code.
+//!
+//! Every helper fails with `ContractError::ArithmeticError` instead of panicking
+//! or wrapping. Products are taken in u128, so any u64 x u64 fits before dividing.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ContractError;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Which way a division that doesn't come out even is rounded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+pub fn checked_add(a: u64, b: u64) -> Result<u64> {
+    a.checked_add(b).ok_or(ContractError::ArithmeticError.into())
+}
+
+pub fn checked_sub(a: u64, b: u64) -> Result<u64> {
+    a.checked_sub(b).ok_or(ContractError::ArithmeticError.into())
+}
+
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| ContractError::ArithmeticError.into())
+}
+
+/// `a * b / denominator` in u128
+pub fn mul_div_u128(a: u128, b: u128, denominator: u128, rounding: Rounding) -> Result<u128> {
+    require!(denominator > 0, ContractError::ArithmeticError);
+    let product = a.checked_mul(b).ok_or(ContractError::ArithmeticError)?;
+    Ok(match rounding {
+        Rounding::Down => product / denominator,
+        Rounding::Up => product.div_ceil(denominator),
+    })
+}
+
+/// `a * b / denominator` with a u128 intermediate, the result has to fit in a u64
+pub fn mul_div(a: u64, b: u64, denominator: u64, rounding: Rounding) -> Result<u64> {
+    to_u64(mul_div_u128(a as u128, b as u128, denominator as u128, rounding)?)
+}
+
+/// `bps` basis points of `value`
+pub fn bps_mul(value: u64, bps: u64, rounding: Rounding) -> Result<u64> {
+    mul_div(value, bps, BPS_DENOMINATOR, rounding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_div_rounds_in_the_requested_direction() {
+        assert_eq!(mul_div(10, 1, 3, Rounding::Down).unwrap(), 3);
+        assert_eq!(mul_div(10, 1, 3, Rounding::Up).unwrap(), 4);
+        // Exact results are not rounded up
+        assert_eq!(mul_div(9, 1, 3, Rounding::Up).unwrap(), 3);
+        // The product may exceed u64 as long as the result doesn't
+        assert_eq!(mul_div(u64::MAX, u64::MAX, u64::MAX, Rounding::Down).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn bad_input_is_an_error_not_a_panic() {
+        assert!(mul_div(1, 1, 0, Rounding::Down).is_err());
+        assert!(mul_div(u64::MAX, 2, 1, Rounding::Down).is_err());
+        assert!(mul_div_u128(u128::MAX, 2, 1, Rounding::Up).is_err());
+        assert!(checked_add(u64::MAX, 1).is_err());
+        assert!(checked_sub(0, 1).is_err());
+        assert!(to_u64(u64::MAX as u128 + 1).is_err());
+    }
+
+    #[test]
+    fn bps_mul_takes_a_share() {
+        assert_eq!(bps_mul(1_000_000, 100, Rounding::Down).unwrap(), 10_000);
+        assert_eq!(bps_mul(999, 100, Rounding::Down).unwrap(), 9);
+        assert_eq!(bps_mul(999, 100, Rounding::Up).unwrap(), 10);
+        assert_eq!(bps_mul(u64::MAX, BPS_DENOMINATOR, Rounding::Down).unwrap(), u64::MAX);
+    }
+}
@@ -2,34 +2,60 @@ use std::fmt;
 
 use anchor_lang::prelude::*;
 
-use crate::{ errors::ContractError, Global };
+use crate::{
+    curve_math::{ self, Reserves },
+    errors::ContractError,
+    math::{ self, Rounding },
+    FeeSchedule,
+    LaunchConfigParams,
+};
 
 use super::BondingCurveLockerCtx;
 
-pub fn bps_mul(bps: u64, value: u64, divisor: u64) -> Option<u64> {
-    bps_mul_raw(bps, value, divisor).unwrap().try_into().ok()
-}
-
-pub fn bps_mul_raw(bps: u64, value: u64, divisor: u64) -> Option<u128> {
-    (value as u128).checked_mul(bps as u128)?.checked_div(divisor as u128)
-}
-
 #[account]
 #[derive(InitSpace, Debug, Default)]
 pub struct BondingCurve {
     pub mint: Pubkey,
     pub creator: Pubkey,
     pub initial_virtual_token_reserves: u64,
+    pub initial_real_token_reserves: u64,
     pub virtual_sol_reserves: u64,
     pub virtual_token_reserves: u64,
     pub real_sol_reserves: u64,
     pub real_token_reserves: u64,
     pub token_total_supply: u64,
+    pub mint_decimals: u8,
     pub start_time: i64,
+    /// Slot trading opens at. Launch timing and fee phases are measured in slots.
+    pub start_slot: u64,
     pub complete: bool,
     pub bump: u8,
     pub sol_raise_target: u64,
     pub realm_pubkey: Pubkey,
+    pub migrated: bool,
+    pub fee_schedule: FeeSchedule,
+    /// Caps the final buy so the raise stops exactly at `sol_raise_target`
+    pub hard_cap: bool,
+    /// Bump of the system owned vault holding the pool SOL. `None` for curves
+    /// created before the vault, which keep SOL on this account until `migrate_sol_vault`.
+    pub sol_vault_bump: Option<u8>,
+    /// Deadline for reaching `sol_raise_target`, the curve turns refundable after it
+    pub end_time: Option<i64>,
+    /// Set once an expired curve starts paying out refunds
+    pub refunding: bool,
+    /// Completes the curve once the implied market cap reaches it
+    pub market_cap_target_lamports: Option<u64>,
+    /// What completed the curve, `None` while it is still trading
+    pub completion_reason: Option<CompletionReason>,
+    /// `LaunchConfig` the curve was created from, `None` for curves using the global settings
+    pub launch_config: Option<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum CompletionReason {
+    SolRaiseTarget,
+    TokensSoldOut,
+    MarketCapTarget,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -38,8 +64,18 @@ pub struct CreateBondingCurveParams {
     pub symbol: String,
     pub uri: String,
     pub start_time: Option<i64>,
+    /// Takes precedence over `start_time`, which is otherwise converted to an estimated slot
+    pub start_slot: Option<u64>,
     pub sol_raise_target: u64,
     pub realm_pubkey: Pubkey,
+    /// Overrides the fee schedule of the launch config, or `Global.fee_schedule`, for this curve
+    pub fee_schedule: Option<FeeSchedule>,
+    /// Fill the final buy only up to `sol_raise_target` and leave the rest with the buyer
+    pub hard_cap: bool,
+    /// Unix timestamp after which an incomplete curve stops trading and refunds holders
+    pub end_time: Option<i64>,
+    /// Complete the curve once `virtual price * token_total_supply` reaches this many lamports
+    pub market_cap_target_lamports: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -48,8 +84,16 @@ pub struct BuyResult {
     pub token_amount: u64,
     /// Amount of SOL that the user paid
     pub sol_amount: u64,
-    /// Price per token in SOL
-    pub price_per_token: f64,
+    /// Amount of SOL the user offered, more than `sol_amount` on a partial fill
+    pub requested_sol_amount: u64,
+    /// Price in lamports per whole token, scaled by `BondingCurve::PRICE_SCALE`
+    pub price_per_token: u128,
+}
+
+impl BuyResult {
+    pub fn is_partial_fill(&self) -> bool {
+        self.sol_amount < self.requested_sol_amount
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -58,60 +102,125 @@ pub struct SellResult {
     pub token_amount: u64,
     /// Amount of SOL that the user will receive
     pub sol_amount: u64,
-    /// Price per token in SOL
-    pub price_per_token: f64,
+    /// Price in lamports per whole token, scaled by `BondingCurve::PRICE_SCALE`
+    pub price_per_token: u128,
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationAmounts {
+    /// SOL sent to the DAO treasury
+    pub treasury_sol_amount: u64,
+    /// SOL paid to the fee receiver for running the migration
+    pub migrate_fee_amount: u64,
+    /// SOL handed over to seed the post-curve liquidity pool
+    pub liquidity_sol_amount: u64,
+    /// Tokens paired with `liquidity_sol_amount` at the final curve price
+    pub liquidity_token_amount: u64,
+    /// Reserved (non-tradable) tokens sent to the DAO treasury
+    pub treasury_token_amount: u64,
+    /// Unsold tradable tokens that are burned
+    pub burned_token_amount: u64,
 }
 
 impl BondingCurve {
     // Change this to match the seed used in CreateBondingCurve account initialization
     pub const SEED_PREFIX: &'static str = "bonding_curve";
+    pub const SOL_VAULT_SEED_PREFIX: &'static str = "sol_vault";
+    pub const LIQUIDITY_VAULT_SEED_PREFIX: &'static str = "liquidity_vault";
+    // Target slot duration, used to turn a future start_time into a start slot
+    pub const MS_PER_SLOT: u64 = 400;
+    // Share of the raised SOL sent to the DAO treasury on migration (80%)
+    pub const MIGRATION_TREASURY_SOL_BPS: u64 = 8_000;
+    // Share of each buy taken back out of the virtual SOL reserves (20%)
+    pub const BUY_VIRTUAL_SOL_DEDUCTION_BPS: u64 = 2_000;
+    // Fixed-point scale of prices, 1e12 keeps sub-lamport precision for cheap tokens
+    pub const PRICE_SCALE: u128 = 1_000_000_000_000;
+
+    pub fn fee_bps(&self, clock: &Clock) -> Result<u64> {
+        let slots_passed = self.slots_since_start(clock);
+        msg!("Start slot: {}, current slot: {} ({} slots)", self.start_slot, clock.slot, slots_passed);
+
+        let fee_bps = curve_math::fee_bps(self, clock.slot)?;
+        msg!("Fee Bps: {}", fee_bps);
+        Ok(fee_bps)
+    }
 
-    pub fn calculate_fee(&self, amount: u64, time_now: i64) -> Result<u64> {
-        let start_time = self.start_time;
-
-        msg!("Start time: {}", start_time);
-        msg!("Current time: {}", time_now);
-
-        let time_diff = time_now - start_time;
-        let slots_passed = time_diff / 400;
-        msg!("Time diff: {} ms ({} slots)", time_diff, slots_passed);
-
-        let mut sol_fee: u64 = 0;
-
-        if slots_passed < 150 {
-            msg!("Phase 1: 99% fees between slot 0 - 150");
-            sol_fee = bps_mul(9900, amount, 10_000).unwrap();
-        } else if slots_passed >= 150 && slots_passed <= 250 {
-            msg!("Phase 2: Linear decrease between 150 - 250");
-
-            // Calculate the minimum fee bps (at slot 250) scaled by 10000 for precision
-            let fee_bps = (-8_300_000_i64)
-                .checked_mul(slots_passed)
-                .ok_or(ContractError::ArithmeticError)?
-                .checked_add(2_162_600_000)
-                .ok_or(ContractError::ArithmeticError)?
-                .checked_div(1_000_000)
-                .ok_or(ContractError::ArithmeticError)?;
-            msg!("Fee Bps: {}", fee_bps);
-
-            sol_fee = bps_mul(fee_bps as u64, amount, 10_000).unwrap();
-        } else if slots_passed > 250 {
-            msg!("Phase 3: 1% fees after 250");
-            sol_fee = bps_mul(100, amount, 10_000).unwrap();
-        }
-        Ok(sol_fee)
+    pub fn calculate_fee(&self, amount: u64, clock: &Clock) -> Result<u64> {
+        curve_math::fee_lamports(amount, self.fee_bps(clock)?)
+    }
+
+    /// Gross amount and fee that leave exactly `net_amount` once the fee is taken,
+    /// for fee-inclusive buys and exact output sells. The fee absorbs the
+    /// rounding, so it is never below `calculate_fee(gross)`.
+    pub fn gross_for_net_after_fee(&self, net_amount: u64, clock: &Clock) -> Result<(u64, u64)> {
+        curve_math::gross_for_net_after_fee(net_amount, self.fee_bps(clock)?)
     }
 
     pub fn get_signer<'a>(bump: &'a u8, mint: &'a Pubkey) -> [&'a [u8]; 3] {
         [Self::SEED_PREFIX.as_bytes(), mint.as_ref(), std::slice::from_ref(bump)]
     }
 
+    pub fn get_sol_vault_signer<'a>(bonding_curve: &'a Pubkey, bump: &'a u8) -> [&'a [u8]; 3] {
+        [Self::SOL_VAULT_SEED_PREFIX.as_bytes(), bonding_curve.as_ref(), std::slice::from_ref(bump)]
+    }
+
+    pub fn get_liquidity_vault_signer<'a>(
+        bonding_curve: &'a Pubkey,
+        bump: &'a u8
+    ) -> [&'a [u8]; 3] {
+        [
+            Self::LIQUIDITY_VAULT_SEED_PREFIX.as_bytes(),
+            bonding_curve.as_ref(),
+            std::slice::from_ref(bump),
+        ]
+    }
+
     pub fn is_started(&self, clock: &Clock) -> bool {
-        let now = clock.unix_timestamp;
-        now >= self.start_time
+        clock.slot >= self.start_slot
+    }
+
+    /// The fundraising window closed before the curve completed
+    pub fn is_expired(&self, clock: &Clock) -> bool {
+        !self.complete && self.end_time.is_some_and(|end_time| clock.unix_timestamp >= end_time)
+    }
+
+    /// `launch_config` is the account passed in for the one the curve was created from
+    pub fn require_launch_config(&self, launch_config: Option<Pubkey>) -> Result<()> {
+        require!(launch_config == self.launch_config, ContractError::InvalidConfig);
+        Ok(())
+    }
+
+    /// Checks of the curve itself every swap goes through, shared with `quote` so a
+    /// quote fails wherever the swap would
+    pub fn require_tradable(&self, clock: &Clock, launch_config: Option<Pubkey>) -> Result<()> {
+        require!(self.sol_vault_bump.is_some(), ContractError::SolVaultNotMigrated);
+        require!(self.is_started(clock), ContractError::CurveNotStarted);
+        require!(!self.refunding && !self.is_expired(clock), ContractError::CurveExpired);
+        self.require_launch_config(launch_config)
     }
 
-    pub fn msg(&self) -> () {
+    pub fn slots_since_start(&self, clock: &Clock) -> u64 {
+        clock.slot.saturating_sub(self.start_slot)
+    }
+
+    /// Launch slot from the creation params: an explicit slot wins, a future
+    /// `start_time` is converted using the target slot duration. That is an
+    /// approximation, slots usually take longer than `MS_PER_SLOT`, so by wall clock
+    /// trading opens at or after `start_time`. Pass `start_slot` to pin the launch.
+    pub fn start_slot_from_params(params: &CreateBondingCurveParams, clock: &Clock) -> u64 {
+        if let Some(start_slot) = params.start_slot {
+            return start_slot;
+        }
+        let seconds_until_start = params.start_time
+            .map(|start_time| start_time.saturating_sub(clock.unix_timestamp).max(0) as u64)
+            .unwrap_or(0);
+        let slots_until_start = seconds_until_start
+            .saturating_mul(1_000)
+            .div_ceil(Self::MS_PER_SLOT);
+        clock.slot.saturating_add(slots_until_start)
+    }
+
+    pub fn msg(&self) {
         msg!("{:#?}", self);
     }
 
@@ -119,7 +228,7 @@ impl BondingCurve {
         &mut self,
         mint: Pubkey,
         creator: Pubkey,
-        global_config: &Global,
+        launch_params: &LaunchConfigParams,
         params: &CreateBondingCurveParams,
         clock: &Clock,
         bump: u8
@@ -129,265 +238,331 @@ impl BondingCurve {
         } else {
             clock.unix_timestamp
         };
-        let creator = creator;
+        let start_slot = Self::start_slot_from_params(params, clock);
         let complete = false;
 
         let sol_raise_target = params.sol_raise_target;
         let realm_pubkey = params.realm_pubkey;
+        let fee_schedule = params.fee_schedule
+            .clone()
+            .unwrap_or_else(|| launch_params.fee_schedule.clone());
 
-        // Important: Use full token supply for virtual reserves (100M),
+        // Important: the default virtual reserves are the full token supply (100M),
         // but only 50% is actually tradable
         self.clone_from(
             &(BondingCurve {
                 mint,
                 creator,
-                // Use FULL token supply for virtual reserves to accurately represent price
-                virtual_token_reserves: global_config.token_total_supply, // 100M tokens
-                virtual_sol_reserves: global_config.initial_virtual_sol_reserves,
-                initial_virtual_token_reserves: global_config.token_total_supply,
+                virtual_token_reserves: launch_params.initial_virtual_token_reserves,
+                virtual_sol_reserves: launch_params.initial_virtual_sol_reserves,
+                initial_virtual_token_reserves: launch_params.initial_virtual_token_reserves,
+                initial_real_token_reserves: launch_params.initial_real_token_reserves,
                 real_sol_reserves: 0,
                 // Only 50% of tokens available for trading
-                real_token_reserves: global_config.initial_real_token_reserves, // 50M tokens
-                token_total_supply: global_config.token_total_supply,
+                real_token_reserves: launch_params.initial_real_token_reserves, // 50M tokens
+                token_total_supply: launch_params.token_total_supply,
+                mint_decimals: launch_params.mint_decimals,
                 start_time,
+                start_slot,
                 complete,
                 bump,
                 sol_raise_target,
                 realm_pubkey,
+                migrated: false,
+                fee_schedule,
+                hard_cap: params.hard_cap,
+                sol_vault_bump: None,
+                end_time: params.end_time,
+                refunding: false,
+                market_cap_target_lamports: params.market_cap_target_lamports,
+                completion_reason: None,
+                launch_config: None,
             })
         );
         self
     }
 
-    pub fn apply_buy(&mut self, mut sol_amount: u64) -> Option<BuyResult> {
+    pub fn apply_buy(&mut self, sol_amount: u64) -> Result<BuyResult> {
         msg!("Applying buy: {}", sol_amount);
-        // Check if we're reaching or exceeding the SOL raise target
-        if self.sol_raise_target > 0 {
-            let potential_new_sol_reserves = self.real_sol_reserves.checked_add(sol_amount)?;
-            if potential_new_sol_reserves >= self.sol_raise_target {
-                msg!("SOL raise target of {} reached or exceeded.", self.sol_raise_target);
-                // Mark as complete (will trigger migration path later)
-                // But don't adjust the amount - let the user buy as much as they want
-                self.complete = true;
-            }
+        let (filled_sol_amount, token_amount) = curve_math::buy_fill(self, sol_amount)?;
+        msg!("Token amount: {:?}", token_amount);
+        if filled_sol_amount < sol_amount {
+            msg!("ApplyBuy: filled {} of {} lamports", filled_sol_amount, sol_amount);
         }
 
-        let mut token_amount = self.get_tokens_for_buy_sol(sol_amount)?;
-        msg!("Token amount: {:?}", token_amount);
+        self.apply_buy_amounts(filled_sol_amount, token_amount, sol_amount)
+    }
 
-        // Last buy because not enough tokens left
-        if token_amount >= self.real_token_reserves {
-            // This is the real constraint - we can't sell more tokens than we have
-            token_amount = self.real_token_reserves;
-
-            // temporarily store the current state
-            let current_virtual_token_reserves = self.virtual_token_reserves;
-            let current_virtual_sol_reserves = self.virtual_sol_reserves;
-
-            // update self with new token amount
-            self.virtual_token_reserves = (current_virtual_token_reserves as u128)
-                .checked_sub(token_amount as u128)?
-                .try_into()
-                .ok()?;
-            self.virtual_sol_reserves = 115_005_359_056; // Total raise amount
-            let recomputed_sol_amount = self.get_sol_for_sell_tokens(token_amount)?;
-            msg!("ApplyBuy: recomputed_sol_amount: {}", recomputed_sol_amount);
-
-            sol_amount = recomputed_sol_amount;
-
-            // Restore the state with the recomputed sol_amount
-            self.virtual_token_reserves = current_virtual_token_reserves;
-            self.virtual_sol_reserves = current_virtual_sol_reserves;
-
-            // Set complete to true because we've sold all tokens
-            self.complete = true;
-            msg!("All tokens sold - bonding curve marked as complete");
+    /// Buy exactly `token_amount` tokens, paying the rounded-up curve cost
+    pub fn apply_buy_exact_out(&mut self, token_amount: u64) -> Result<BuyResult> {
+        msg!("Applying exact out buy: {}", token_amount);
+        if token_amount > self.real_token_reserves {
+            msg!("ApplyBuy: only {} tokens left on the curve", self.real_token_reserves);
+            return Err(ContractError::InsufficientCurveReserves.into());
         }
 
-        // Adjusting token reserve values
-        // New Virtual Token Reserves
-        let new_virtual_token_reserves = (self.virtual_token_reserves as u128).checked_sub(
-            token_amount as u128
-        )?;
-        msg!("ApplyBuy: new_virtual_token_reserves: {}", new_virtual_token_reserves);
+        let sol_amount = self.get_sol_for_buy_tokens(token_amount)?;
+        msg!("ApplyBuy: sol_amount: {}", sol_amount);
 
-        // New Real Token Reserves
-        let new_real_token_reserves = (self.real_token_reserves as u128).checked_sub(
-            token_amount as u128
-        )?;
-        msg!("ApplyBuy: new_real_token_reserves: {}", new_real_token_reserves);
+        if let Some(remaining) = self.remaining_to_hard_cap() {
+            if sol_amount > remaining {
+                msg!("ApplyBuy: {} lamports left to the hard capped target", remaining);
+                return Err(ContractError::HardCapExceeded.into());
+            }
+        }
 
-        // Adjusting sol reserve values
-        // New Virtual Sol Reserves
-        let new_virtual_sol_reserves = (self.virtual_sol_reserves as u128).checked_add(
-            sol_amount as u128
-        )?;
-        msg!("ApplyBuy: new_virtual_sol_reserves: {}", new_virtual_sol_reserves);
+        self.apply_buy_amounts(sol_amount, token_amount, sol_amount)
+    }
 
-        // New Real Sol Reserves
-        let new_real_sol_reserves = (self.real_sol_reserves as u128).checked_add(
-            sol_amount as u128
-        )?;
-        msg!("ApplyBuy: new_real_sol_reserves: {}", new_real_sol_reserves);
+    /// The first trigger to fire is kept as the reason
+    fn mark_complete(&mut self, reason: CompletionReason) {
+        self.complete = true;
+        self.completion_reason.get_or_insert(reason);
+    }
 
-        // Calculate price per token
-        let price_per_token = if token_amount > 0 {
-            (sol_amount as f64) / (token_amount as f64)
-        } else {
-            0.0
-        };
+    /// Implied market cap: the virtual reserve price applied to the full supply
+    pub fn market_cap_lamports(&self) -> Result<u64> {
+        curve_math::market_cap_lamports(
+            self.virtual_sol_reserves,
+            self.virtual_token_reserves,
+            self.token_total_supply
+        )
+    }
 
-        // Calculate treasury portion (20% of SOL)
-        let treasury_portion = (sol_amount as u128)
-            .checked_mul(2000)
-            .and_then(|amt| amt.checked_div(10000))
-            .and_then(|amt| u64::try_from(amt).ok())?;
+    /// Lamports left before a hard capped curve reaches its target
+    pub fn remaining_to_hard_cap(&self) -> Option<u64> {
+        if !self.hard_cap || self.sol_raise_target == 0 {
+            return None;
+        }
+        Some(self.sol_raise_target.saturating_sub(self.real_sol_reserves))
+    }
 
-        // We no longer need to track treasury allocation
-        // But we still need to reduce virtual_sol_reserves to maintain the curve
-        let new_virtual_sol_reserves = new_virtual_sol_reserves.checked_sub(
-            treasury_portion as u128
+    fn apply_buy_amounts(
+        &mut self,
+        sol_amount: u64,
+        token_amount: u64,
+        requested_sol_amount: u64
+    ) -> Result<BuyResult> {
+        let (reserves, completion_reason) = curve_math::buy_reserves(
+            self,
+            sol_amount,
+            token_amount
         )?;
+        msg!("ApplyBuy: {:?}", reserves);
+        let price_per_token = self.price_per_token(sol_amount, token_amount)?;
+        self.set_reserves(reserves);
+
+        if let Some(reason) = completion_reason {
+            msg!("Bonding curve complete: {:?}", reason);
+            self.mark_complete(reason);
+        }
 
-        self.virtual_token_reserves = new_virtual_token_reserves.try_into().ok()?;
-        self.real_token_reserves = new_real_token_reserves.try_into().ok()?;
-        self.virtual_sol_reserves = new_virtual_sol_reserves.try_into().ok()?;
-        self.real_sol_reserves = new_real_sol_reserves.try_into().ok()?;
         self.msg();
-        Some(BuyResult {
+        Ok(BuyResult {
             token_amount,
             sol_amount,
+            requested_sol_amount,
             price_per_token,
         })
     }
 
-    pub fn apply_sell(&mut self, token_amount: u64) -> Option<SellResult> {
+    pub fn apply_sell(&mut self, token_amount: u64) -> Result<SellResult> {
         msg!("apply_sell: token_amount: {}", token_amount);
 
         // Computing Sol Amount out
         let sol_amount = self.get_sol_for_sell_tokens(token_amount)?;
         msg!("apply_sell: sol_amount: {}", sol_amount);
+        require!(sol_amount > 0, ContractError::ZeroOutput);
 
-        // Check if bonding curve has enough SOL to fulfill the sell request
-        if sol_amount > self.real_sol_reserves {
-            msg!("apply_sell: Not enough SOL reserves to fulfill sell request");
-            return None;
-        }
-
-        // Adjusting token reserve values
-        // New Virtual Token Reserves
-        let new_virtual_token_reserves = (self.virtual_token_reserves as u128).checked_add(
-            token_amount as u128
-        )?;
-        msg!("apply_sell: new_virtual_token_reserves: {}", new_virtual_token_reserves);
-
-        // New Real Token Reserves
-        let new_real_token_reserves = (self.real_token_reserves as u128).checked_add(
-            token_amount as u128
-        )?;
-        msg!("apply_sell: new_real_token_reserves: {}", new_real_token_reserves);
+        self.apply_sell_amounts(token_amount, sol_amount)
+    }
 
-        // Adjusting sol reserve values
-        // New Virtual Sol Reserves
-        let new_virtual_sol_reserves = (self.virtual_sol_reserves as u128).checked_sub(
-            sol_amount as u128
-        )?;
-        msg!("apply_sell: new_virtual_sol_reserves: {}", new_virtual_sol_reserves);
+    /// Sell for exactly `sol_amount` lamports, taking the rounded-up token cost
+    pub fn apply_sell_exact_out(&mut self, sol_amount: u64) -> Result<SellResult> {
+        msg!("apply_sell: exact sol_amount: {}", sol_amount);
 
-        // New Real Sol Reserves
-        let new_real_sol_reserves = self.real_sol_reserves.checked_sub(sol_amount)?;
-        msg!("apply_sell: new_real_sol_reserves: {}", new_real_sol_reserves);
+        let token_amount = self.get_tokens_for_sell_sol(sol_amount)?;
+        msg!("apply_sell: token_amount: {}", token_amount);
 
-        // Calculate price per token
-        let price_per_token = if token_amount > 0 {
-            (sol_amount as f64) / (token_amount as f64)
-        } else {
-            0.0
-        };
+        self.apply_sell_amounts(token_amount, sol_amount)
+    }
 
-        self.virtual_token_reserves = new_virtual_token_reserves.try_into().ok()?;
-        self.real_token_reserves = new_real_token_reserves.try_into().ok()?;
-        self.virtual_sol_reserves = new_virtual_sol_reserves.try_into().ok()?;
-        self.real_sol_reserves = new_real_sol_reserves;
+    fn apply_sell_amounts(&mut self, token_amount: u64, sol_amount: u64) -> Result<SellResult> {
+        let reserves = curve_math::sell_reserves(self, token_amount, sol_amount)?;
+        msg!("apply_sell: {:?}", reserves);
+        let price_per_token = self.price_per_token(sol_amount, token_amount)?;
+        self.set_reserves(reserves);
 
         msg!("apply_sell: updated state successfully");
         self.msg();
 
-        Some(SellResult {
+        Ok(SellResult {
             token_amount,
             sol_amount,
             price_per_token,
         })
     }
 
-    pub fn get_tokens_for_buy_sol(&self, sol_amount: u64) -> Option<u64> {
-        if sol_amount == 0 {
-            return None;
+    fn set_reserves(&mut self, reserves: Reserves) {
+        self.virtual_sol_reserves = reserves.virtual_sol_reserves;
+        self.virtual_token_reserves = reserves.virtual_token_reserves;
+        self.real_sol_reserves = reserves.real_sol_reserves;
+        self.real_token_reserves = reserves.real_token_reserves;
+    }
+
+    /// Tokens bought from the curve and still held outside of it
+    pub fn circulating_token_amount(&self) -> Option<u64> {
+        self.initial_real_token_reserves.checked_sub(self.real_token_reserves)
+    }
+
+    /// Takes back `token_amount` tokens for their pro-rata share of `real_sol_reserves`.
+    /// Rounded down, so holders refunding later never get less than earlier ones.
+    pub fn apply_refund(&mut self, token_amount: u64) -> Result<u64> {
+        let circulating_token_amount =
+            self.circulating_token_amount().ok_or(ContractError::ArithmeticError)?;
+        require!(token_amount > 0, ContractError::MinSwap);
+        if token_amount > circulating_token_amount {
+            msg!("apply_refund: {} of {} circulating tokens", token_amount, circulating_token_amount);
+            return Err(ContractError::RefundFailed.into());
         }
-        msg!("GetTokensForBuySol: sol_amount: {}", sol_amount);
 
-        // Calculate the product of the reserves (decimal adjusted)
-        let product_of_reserves = (self.virtual_sol_reserves as u128)
-            .checked_div(1_000_000_000)
-            ? // Divide by 9 decimals
-            .checked_mul((self.virtual_token_reserves as u128).checked_div(1_000_000)?)
-            ? // Divide by 6 decimals
-            .checked_mul(1_000_000_000)?; // Scaling factor
-
-        msg!("GetTokensForBuySol: product_of_reserves: {}", product_of_reserves);
-        let new_virtual_sol_reserves = (self.virtual_sol_reserves as u128).checked_add(
-            sol_amount as u128
-        )?;
-        msg!("GetTokensForBuySol: new_virtual_sol_reserves: {}", new_virtual_sol_reserves);
-        let new_virtual_token_reserves = product_of_reserves
-            .checked_div(new_virtual_sol_reserves)?
-            .checked_mul(1_000_000)?; // Scale up to proper decimals again;
-
-        msg!("GetTokensForBuySol: new_virtual_token_reserves: {}", new_virtual_token_reserves);
-        let tokens_received = (self.virtual_token_reserves as u128).checked_sub(
-            new_virtual_token_reserves
+        let sol_amount = math::mul_div(
+            self.real_sol_reserves,
+            token_amount,
+            circulating_token_amount,
+            Rounding::Down
         )?;
+        msg!("apply_refund: token_amount: {}, sol_amount: {}", token_amount, sol_amount);
+
+        self.real_sol_reserves = math::checked_sub(self.real_sol_reserves, sol_amount)?;
+        self.real_token_reserves = math::checked_add(self.real_token_reserves, token_amount)?;
+        Ok(sol_amount)
+    }
+
+    /// Tokens out for `sol_amount` lamports in, see `curve_math::tokens_for_buy_sol`
+    pub fn get_tokens_for_buy_sol(&self, sol_amount: u64) -> Result<u64> {
+        msg!("GetTokensForBuySol: sol_amount: {}", sol_amount);
+        let tokens_received = curve_math::tokens_for_buy_sol(self, sol_amount)?;
         msg!("GetTokensForBuySol: tokens_received: {}", tokens_received);
+        Ok(tokens_received)
+    }
 
-        let recv = <u128 as std::convert::TryInto<u64>>::try_into(tokens_received).ok()?;
-        msg!("GetTokensForBuySol: recv: {}", recv);
-        Some(recv)
+    /// Lamports needed to take `token_amount` tokens out of the curve, see
+    /// `curve_math::sol_for_buy_tokens`
+    pub fn get_sol_for_buy_tokens(&self, token_amount: u64) -> Result<u64> {
+        let sol_required = curve_math::sol_for_buy_tokens(self, token_amount)?;
+        msg!("GetSolForBuyTokens: sol_required: {}", sol_required);
+        Ok(sol_required)
     }
 
-    pub fn get_sol_for_sell_tokens(&self, token_amount: u64) -> Option<u64> {
-        if token_amount == 0 {
-            return None;
-        }
+    /// Lamports out for `token_amount` tokens in, see `curve_math::sol_for_sell_tokens`
+    pub fn get_sol_for_sell_tokens(&self, token_amount: u64) -> Result<u64> {
         msg!("GetSolForSellTokens: token_amount: {}", token_amount);
         msg!("GetSolForSellTokens: virtual sol reserves: {}", self.virtual_sol_reserves);
         msg!("GetSolForSellTokens: virtual token reserves: {}", self.virtual_token_reserves);
+        let sol_received = curve_math::sol_for_sell_tokens(self, token_amount)?;
+        msg!("GetSolForSellTokens: sol_received: {}", sol_received);
+        Ok(sol_received)
+    }
+
+    /// Tokens that must be sold to take `sol_amount` lamports out of the curve, see
+    /// `curve_math::tokens_for_sell_sol`
+    pub fn get_tokens_for_sell_sol(&self, sol_amount: u64) -> Result<u64> {
+        let tokens_required = curve_math::tokens_for_sell_sol(self, sol_amount)?;
+        msg!("GetTokensForSellSol: tokens_required: {}", tokens_required);
+        Ok(tokens_required)
+    }
+
+    /// Price in lamports per whole token (`10^mint_decimals` base units), scaled by
+    /// `PRICE_SCALE` and rounded down
+    pub fn price_per_token(&self, sol_amount: u64, token_amount: u64) -> Result<u128> {
+        if token_amount == 0 {
+            return Ok(0);
+        }
+        let scaled_sol_amount = (10u128)
+            .checked_pow(self.mint_decimals as u32)
+            .and_then(|whole_token| whole_token.checked_mul(sol_amount as u128))
+            .ok_or(ContractError::ArithmeticError)?;
+        math::mul_div_u128(
+            scaled_sol_amount,
+            Self::PRICE_SCALE,
+            token_amount as u128,
+            Rounding::Down
+        )
+    }
 
-        // Calculate the product of the reserves (decimal adjusted)
-        let product_of_reserves = (self.virtual_sol_reserves as u128)
-            .checked_div(1_000_000_000)
-            ? // Divide by 9 decimals
-            .checked_mul((self.virtual_token_reserves as u128).checked_div(1_000_000)?)
-            ? // Divide by 6 decimals
-            .checked_mul(1_000_000_000)?; // Scaling factor
-
-        msg!("GetSolForSellTokens: product_of_reserves: {}", product_of_reserves);
-        let new_virtual_token_reserves = (self.virtual_token_reserves as u128).checked_add(
-            token_amount as u128
+    pub fn calculate_migration(
+        &self,
+        token_balance: u64,
+        migrate_fee_amount: u64
+    ) -> Result<MigrationAmounts> {
+        // 80% of the raised SOL goes to the DAO treasury
+        let treasury_sol_amount = math::bps_mul(
+            self.real_sol_reserves,
+            Self::MIGRATION_TREASURY_SOL_BPS,
+            Rounding::Down
         )?;
-        msg!("GetSolForSellTokens: new_virtual_token_reserves: {}", new_virtual_token_reserves);
-        let new_virtual_sol_reserves = product_of_reserves
-            .checked_div(new_virtual_token_reserves)?
-            .checked_mul(1_000_000)?; // Scale up to proper decimals again;
-
-        msg!("GetSolForSellTokens: new_virtual_sol_reserves: {}", new_virtual_sol_reserves);
-        let sol_received = (self.virtual_sol_reserves as u128).checked_sub(
-            new_virtual_sol_reserves
+
+        // The rest seeds the post-curve liquidity, minus the migration fee
+        let remaining_sol = math::checked_sub(self.real_sol_reserves, treasury_sol_amount)?;
+        let migrate_fee_amount = migrate_fee_amount.min(remaining_sol);
+        let liquidity_sol_amount = math::checked_sub(remaining_sol, migrate_fee_amount)?;
+
+        // Unsold tradable tokens are burned, everything else in the ATA is the
+        // reserved (non-tradable) part of the supply
+        let burned_token_amount = self.real_token_reserves;
+        let reserved_token_amount = math::checked_sub(token_balance, burned_token_amount)?;
+
+        // Pair the liquidity SOL with tokens at the final curve price so trading
+        // continues where the curve stopped
+        let liquidity_token_amount = math::mul_div(
+            liquidity_sol_amount,
+            self.virtual_token_reserves,
+            self.virtual_sol_reserves,
+            Rounding::Down
+        )?.min(reserved_token_amount);
+        let treasury_token_amount = math::checked_sub(
+            reserved_token_amount,
+            liquidity_token_amount
         )?;
-        msg!("GetSolForSellTokens: sol_received: {}", sol_received);
 
-        let recv = <u128 as std::convert::TryInto<u64>>::try_into(sol_received).ok()?;
-        msg!("GetSolForSellTokens: recv: {}", recv);
-        Some(recv)
+        Ok(MigrationAmounts {
+            treasury_sol_amount,
+            migrate_fee_amount,
+            liquidity_sol_amount,
+            liquidity_token_amount,
+            treasury_token_amount,
+            burned_token_amount,
+        })
+    }
+
+    /// Tokens held by the curve ATA that are not part of `real_token_reserves`
+    pub fn reserved_token_amount(&self) -> Result<u64> {
+        if self.migrated {
+            return Ok(0);
+        }
+        math::checked_sub(self.token_total_supply, self.initial_real_token_reserves)
+    }
+
+    /// Lamports in the SOL vault and tokens in the curve ATA on top of the tracked
+    /// reserves. Fails with `BondingCurveInvariant` if either balance is short.
+    pub fn surplus(&self, lamports: u64, rent_exemption_balance: u64, token_balance: u64) -> Result<(u64, u64)> {
+        let expected_lamports = math::checked_add(self.real_sol_reserves, rent_exemption_balance)?;
+        let sol_surplus = lamports
+            .checked_sub(expected_lamports)
+            .ok_or(ContractError::BondingCurveInvariant)?;
+
+        // The ATA also holds the reserved (non-tradable) part of the supply until migration.
+        // Use the curve's own snapshot so global settings changes don't affect live curves.
+        let expected_tokens = math::checked_add(
+            self.real_token_reserves,
+            self.reserved_token_amount()?
+        )?;
+        let token_surplus = token_balance
+            .checked_sub(expected_tokens)
+            .ok_or(ContractError::BondingCurveInvariant)?;
+
+        Ok((sol_surplus, token_surplus))
     }
 
     pub fn invariant(ctx: &mut BondingCurveLockerCtx) -> Result<()> {
@@ -403,48 +578,36 @@ impl BondingCurve {
         }
 
         tkn_account.reload()?;
-        let lamports: u64 = bonding_curve.get_lamports();
-        let mut tkn_amount = tkn_account.amount;
-        if tkn_amount + ctx.global.initial_real_token_reserves >= ctx.global.token_total_supply {
-            tkn_amount = tkn_amount
-                .checked_add(ctx.global.initial_real_token_reserves)
-                .ok_or(ContractError::ArithmeticError)?
-                .checked_sub(ctx.global.token_total_supply)
-                .ok_or(ContractError::ArithmeticError)?;
-        }
-        let rent_exemption_balance: u64 = Rent::get()?.minimum_balance(
-            8 + (BondingCurve::INIT_SPACE as usize)
-        );
-        let bonding_curve_pool_lamports: u64 = lamports - rent_exemption_balance;
-        // Ensure real sol reserves are equal to bonding curve pool lamports
-        if bonding_curve_pool_lamports != bonding_curve.real_sol_reserves {
-            msg!(
-                "real_sol_r:{}, bonding_lamps:{}",
-                bonding_curve.real_sol_reserves,
-                bonding_curve_pool_lamports
-            );
-            msg!("Invariant failed: real_sol_reserves != bonding_curve_pool_lamports");
-            return Err(ContractError::BondingCurveInvariant.into());
+        // Pool SOL lives in the vault, which only has to stay rent exempt itself
+        let lamports: u64 = ctx.sol_vault.get_lamports();
+        let rent_exemption_balance: u64 = Rent::get()?.minimum_balance(0);
+        // Tracked reserves are the source of truth, anything on top of them was
+        // donated and can be skimmed, but must never block trading
+        let (sol_surplus, token_surplus) = bonding_curve
+            .surplus(lamports, rent_exemption_balance, tkn_account.amount)
+            .inspect_err(|_| {
+                msg!(
+                    "Invariant failed: real_sol_reserves: {}, lamports: {}, real_token_reserves: {}, tkn_amount: {}",
+                    bonding_curve.real_sol_reserves,
+                    lamports,
+                    bonding_curve.real_token_reserves,
+                    tkn_account.amount
+                );
+            })?;
+        if sol_surplus > 0 || token_surplus > 0 {
+            msg!("Surplus on the curve: {} lamports, {} tokens", sol_surplus, token_surplus);
         }
 
         // Ensure the virtual reserves are always positive
-        if bonding_curve.virtual_sol_reserves <= 0 {
+        if bonding_curve.virtual_sol_reserves == 0 {
             msg!("Invariant failed: virtual_sol_reserves <= 0");
             return Err(ContractError::BondingCurveInvariant.into());
         }
-        if bonding_curve.virtual_token_reserves <= 0 {
+        if bonding_curve.virtual_token_reserves == 0 {
             msg!("Invariant failed: virtual_token_reserves <= 0");
             return Err(ContractError::BondingCurveInvariant.into());
         }
 
-        // Ensure the token total supply is consistent with the reserves
-        if bonding_curve.real_token_reserves != tkn_amount {
-            msg!("Invariant failed: real_token_reserves != tkn_amount");
-            msg!("real_token_reserves: {}", bonding_curve.real_token_reserves);
-            msg!("real_token_reserves: {}", bonding_curve.token_total_supply);
-            msg!("tkn_amount: {}", tkn_amount);
-            return Err(ContractError::BondingCurveInvariant.into());
-        }
         Ok(())
     }
 }
@@ -453,7 +616,7 @@ impl fmt::Display for BondingCurve {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "BondingCurve {{ creator: {:?}, initial_virtual_token_reserves: {:?}, virtual_sol_reserves: {:?}, virtual_token_reserves: {:?}, real_sol_reserves: {:?}, real_token_reserves: {:?}, token_total_supply: {:?}, start_time: {:?}, complete: {:?} }}",
+            "BondingCurve {{ creator: {:?}, initial_virtual_token_reserves: {:?}, virtual_sol_reserves: {:?}, virtual_token_reserves: {:?}, real_sol_reserves: {:?}, real_token_reserves: {:?}, token_total_supply: {:?}, start_time: {:?}, start_slot: {:?}, complete: {:?} }}",
             self.creator,
             self.initial_virtual_token_reserves,
             self.virtual_sol_reserves,
@@ -462,7 +625,550 @@ impl fmt::Display for BondingCurve {
             self.real_token_reserves,
             self.token_total_supply,
             self.start_time,
+            self.start_slot,
             self.complete
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FeeBreakpoint;
+
+    fn clock_at(slot: u64, unix_timestamp: i64) -> Clock {
+        Clock { slot, unix_timestamp, ..Clock::default() }
+    }
+
+    fn curve_starting_at(start_slot: u64, start_time: i64) -> BondingCurve {
+        BondingCurve {
+            start_slot,
+            start_time,
+            fee_schedule: FeeSchedule::default(),
+            ..BondingCurve::default()
+        }
+    }
+
+    #[test]
+    fn fee_phases_follow_slots() {
+        let curve = curve_starting_at(1_000, 1_700_000_000);
+        let amount = 1_000_000_000;
+        // (slots after launch, expected fee in bps)
+        let phases = [
+            (0, 9_900),
+            (149, 9_900),
+            (150, 917),
+            (151, 909),
+            (200, 502),
+            (250, 87),
+            (251, 100),
+            (100_000, 100),
+        ];
+        for (elapsed, fee_bps) in phases {
+            // Warp the timestamp along at 400ms per slot, it must not matter
+            let clock = clock_at(1_000 + elapsed, 1_700_000_000 + ((elapsed * 2) / 5) as i64);
+            let fee = curve.calculate_fee(amount, &clock).unwrap();
+            assert_eq!(fee, (amount * fee_bps) / 10_000, "slot +{}", elapsed);
+        }
+    }
+
+    #[test]
+    fn timestamp_does_not_affect_fee_or_start() {
+        let curve = curve_starting_at(1_000, 1_700_000_000);
+        // A day later by wall clock but still on the launch slot
+        let clock = clock_at(1_000, 1_700_086_400);
+        assert_eq!(curve.calculate_fee(10_000, &clock).unwrap(), 9_900);
+
+        // Timestamp past start_time, slot before launch
+        assert!(!curve.is_started(&clock_at(999, 1_700_086_400)));
+        assert!(curve.is_started(&clock_at(1_000, 1_600_000_000)));
+    }
+
+    #[test]
+    fn start_slot_from_params() {
+        let clock = clock_at(5_000, 1_700_000_000);
+        let mut params = CreateBondingCurveParams {
+            name: String::new(),
+            symbol: String::new(),
+            uri: String::new(),
+            start_time: None,
+            start_slot: None,
+            sol_raise_target: 0,
+            realm_pubkey: Pubkey::default(),
+            fee_schedule: None,
+            hard_cap: false,
+            end_time: None,
+            market_cap_target_lamports: None,
+        };
+        assert_eq!(BondingCurve::start_slot_from_params(&params, &clock), 5_000);
+
+        // One minute ahead is 150 slots at 400ms
+        params.start_time = Some(1_700_000_060);
+        assert_eq!(BondingCurve::start_slot_from_params(&params, &clock), 5_150);
+
+        params.start_slot = Some(9_000);
+        assert_eq!(BondingCurve::start_slot_from_params(&params, &clock), 9_000);
+    }
+
+    #[test]
+    fn update_from_params_copies_launch_params() {
+        let launch_params = LaunchConfigParams {
+            initial_virtual_token_reserves: 1_073_000_000_000_000_000,
+            initial_virtual_sol_reserves: 40_000_000_000,
+            initial_real_token_reserves: 793_100_000_000_000_000,
+            token_total_supply: 1_000_000_000_000_000_000,
+            mint_decimals: 9,
+            fee_schedule: FeeSchedule {
+                breakpoints: vec![FeeBreakpoint { elapsed: 0, fee_bps: 50 }],
+            },
+            min_sol_raise_target: 0,
+            max_sol_raise_target: u64::MAX,
+        };
+        let mut params = CreateBondingCurveParams {
+            name: String::new(),
+            symbol: String::new(),
+            uri: String::new(),
+            start_time: None,
+            start_slot: None,
+            sol_raise_target: 85_000_000_000,
+            realm_pubkey: Pubkey::default(),
+            fee_schedule: None,
+            hard_cap: false,
+            end_time: None,
+            market_cap_target_lamports: None,
+        };
+        let clock = clock_at(5_000, 1_700_000_000);
+
+        let (mint, creator) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let mut curve = BondingCurve::default();
+        curve.update_from_params(mint, creator, &launch_params, &params, &clock, 1);
+        assert_eq!(curve.virtual_sol_reserves, 40_000_000_000);
+        assert_eq!(curve.virtual_token_reserves, 1_073_000_000_000_000_000);
+        assert_eq!(curve.initial_virtual_token_reserves, 1_073_000_000_000_000_000);
+        assert_eq!(curve.real_token_reserves, 793_100_000_000_000_000);
+        assert_eq!(curve.initial_real_token_reserves, 793_100_000_000_000_000);
+        assert_eq!(curve.token_total_supply, 1_000_000_000_000_000_000);
+        assert_eq!(curve.mint_decimals, 9);
+        assert_eq!(curve.fee_schedule, launch_params.fee_schedule);
+
+        // The creator's fee schedule wins over the launch config's
+        params.fee_schedule = Some(FeeSchedule::default());
+        curve.update_from_params(mint, creator, &launch_params, &params, &clock, 1);
+        assert_eq!(curve.fee_schedule, FeeSchedule::default());
+    }
+
+    #[test]
+    fn launch_config_must_match() {
+        let launch_config = Pubkey::new_unique();
+        let curve = BondingCurve { launch_config: Some(launch_config), ..BondingCurve::default() };
+        assert!(curve.require_launch_config(Some(launch_config)).is_ok());
+        assert!(curve.require_launch_config(Some(Pubkey::new_unique())).is_err());
+        assert!(curve.require_launch_config(None).is_err());
+
+        let curve = BondingCurve::default();
+        assert!(curve.require_launch_config(None).is_ok());
+        assert!(curve.require_launch_config(Some(launch_config)).is_err());
+    }
+
+    #[test]
+    fn tradable_checks_match_swap() {
+        let launch_config = Pubkey::new_unique();
+        let mut curve = BondingCurve {
+            launch_config: Some(launch_config),
+            ..curve_starting_at(1_000, 1_700_000_000)
+        };
+        let started = clock_at(1_000, 1_700_000_000);
+        assert_eq!(
+            curve.require_tradable(&started, Some(launch_config)).unwrap_err(),
+            ContractError::SolVaultNotMigrated.into()
+        );
+
+        curve.sol_vault_bump = Some(255);
+        assert!(curve.require_tradable(&started, Some(launch_config)).is_ok());
+        assert_eq!(
+            curve.require_tradable(&clock_at(999, 1_700_000_000), Some(launch_config)).unwrap_err(),
+            ContractError::CurveNotStarted.into()
+        );
+        assert_eq!(
+            curve.require_tradable(&started, None).unwrap_err(),
+            ContractError::InvalidConfig.into()
+        );
+
+        curve.refunding = true;
+        assert_eq!(
+            curve.require_tradable(&started, Some(launch_config)).unwrap_err(),
+            ContractError::CurveExpired.into()
+        );
+    }
+
+    fn curve_with_decimals(mint_decimals: u8) -> BondingCurve {
+        let unit = (10u64).pow(mint_decimals as u32);
+        BondingCurve {
+            virtual_sol_reserves: 30_000_000_000,
+            virtual_token_reserves: 1_073_000_000 * unit,
+            real_token_reserves: 793_100_000 * unit,
+            token_total_supply: 1_000_000_000 * unit,
+            mint_decimals,
+            ..BondingCurve::default()
+        }
+    }
+
+    fn k(curve: &BondingCurve) -> u128 {
+        (curve.virtual_sol_reserves as u128) * (curve.virtual_token_reserves as u128)
+    }
+
+    /// Buys price tokens on the full SOL amount, then take `BUY_VIRTUAL_SOL_DEDUCTION_BPS`
+    /// of it back out of the virtual SOL reserves, so k drops on every buy. The quote
+    /// itself never gives k away, sells never lower it and round trips never profit.
+    #[test]
+    fn k_holds_on_quotes_and_round_trips_never_profit() {
+        for mint_decimals in [0u8, 2, 6, 9] {
+            for sol_in in [1u64, 999, 1_000_000, 123_456_789, 5_000_000_000] {
+                let mut curve = curve_with_decimals(mint_decimals);
+                let start = curve_with_decimals(mint_decimals);
+                let k_start = k(&curve);
+
+                // Buy leg
+                if curve.get_tokens_for_buy_sol(sol_in).unwrap() == 0 {
+                    assert!(curve.apply_buy(sol_in).is_err());
+                    continue;
+                }
+                let buy = curve.apply_buy(sol_in).unwrap();
+                assert_eq!(buy.sol_amount, sol_in);
+                let deduction = math::bps_mul(
+                    sol_in,
+                    BondingCurve::BUY_VIRTUAL_SOL_DEDUCTION_BPS,
+                    Rounding::Down
+                ).unwrap();
+                let quoted_virtual_sol_reserves = start.virtual_sol_reserves + sol_in;
+                assert_eq!(curve.virtual_sol_reserves, quoted_virtual_sol_reserves - deduction);
+                assert_eq!(curve.real_sol_reserves, sol_in);
+                let k_quoted =
+                    (quoted_virtual_sol_reserves as u128) * (curve.virtual_token_reserves as u128);
+                assert!(k_quoted >= k_start, "quote d={} sol={}", mint_decimals, sol_in);
+                let k_after_buy = k(&curve);
+                if deduction > 0 {
+                    assert!(k_after_buy < k_start, "buy d={} sol={}", mint_decimals, sol_in);
+                }
+
+                // Sell the same tokens straight back
+                if curve.get_sol_for_sell_tokens(buy.token_amount).unwrap() == 0 {
+                    assert!(curve.apply_sell(buy.token_amount).is_err());
+                    continue;
+                }
+                let sell = curve.apply_sell(buy.token_amount).unwrap();
+                assert!(k(&curve) >= k_after_buy, "sell d={} sol={}", mint_decimals, sol_in);
+                assert!(sell.sol_amount <= sol_in, "profit d={} sol={}", mint_decimals, sol_in);
+
+                // The curve keeps what the round trip lost, and the deduction keeps the price lower
+                assert_eq!(curve.real_sol_reserves, sol_in - sell.sol_amount);
+                assert_eq!(curve.virtual_token_reserves, start.virtual_token_reserves);
+                assert!(curve.virtual_sol_reserves <= start.virtual_sol_reserves);
+            }
+        }
+    }
+
+    #[test]
+    fn quotes_scale_with_mint_decimals() {
+        let sol_in = 1_000_000_000;
+        let base = curve_with_decimals(0).get_tokens_for_buy_sol(sol_in).unwrap();
+        for mint_decimals in [2u8, 6, 9] {
+            let unit = (10u64).pow(mint_decimals as u32);
+            let curve = curve_with_decimals(mint_decimals);
+            let tokens_out = curve.get_tokens_for_buy_sol(sol_in).unwrap();
+            // Same whole-token amount, more precision below the decimal point
+            assert_eq!(tokens_out / unit, base);
+            let price = curve.price_per_token(sol_in, tokens_out).unwrap();
+            let base_price = curve_with_decimals(0).price_per_token(sol_in, base).unwrap();
+            assert!(price.abs_diff(base_price) * 1_000_000 < base_price);
+        }
+    }
+
+    #[test]
+    fn price_per_token_is_fixed_point() {
+        let curve = curve_with_decimals(6);
+        // 1.5 SOL for 3 whole tokens is 0.5 SOL per token
+        assert_eq!(
+            curve.price_per_token(1_500_000_000, 3_000_000).unwrap(),
+            500_000_000 * BondingCurve::PRICE_SCALE
+        );
+        // Fractions of a lamport survive the scaling
+        assert_eq!(curve.price_per_token(1, 3_000_000).unwrap(), 333_333_333_333);
+        assert_eq!(curve.price_per_token(1_000, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn failed_trades_report_why() {
+        let mut curve = curve_with_decimals(0);
+        curve.initial_real_token_reserves = curve.real_token_reserves;
+        assert_eq!(curve.apply_buy(0).unwrap_err(), ContractError::MinSwap.into());
+        // A lamport buys less than one whole token at the starting price
+        assert_eq!(curve.apply_buy(1).unwrap_err(), ContractError::ZeroOutput.into());
+        // Nothing has been bought yet, so there is no SOL to sell into
+        assert_eq!(
+            curve.apply_sell(1_000_000).unwrap_err(),
+            ContractError::InsufficientCurveReserves.into()
+        );
+        assert_eq!(
+            curve.apply_sell_exact_out(curve.virtual_sol_reserves).unwrap_err(),
+            ContractError::InsufficientCurveReserves.into()
+        );
+
+        let mut capped = BondingCurve {
+            sol_raise_target: 1_000_000_000,
+            real_sol_reserves: 1_000_000_000,
+            hard_cap: true,
+            ..curve
+        };
+        assert_eq!(
+            capped.apply_buy(1_000_000).unwrap_err(),
+            ContractError::RaiseTargetReached.into()
+        );
+    }
+
+    #[test]
+    fn apply_round_trip_never_pays_out_more() {
+        for mint_decimals in [0u8, 6, 9] {
+            let mut curve = curve_with_decimals(mint_decimals);
+            curve.initial_real_token_reserves = curve.real_token_reserves;
+            let bought = curve.apply_buy(2_500_000_000).unwrap();
+            let sold = curve.apply_sell(bought.token_amount).unwrap();
+            assert!(sold.sol_amount <= bought.sol_amount, "d={}", mint_decimals);
+        }
+    }
+
+    #[test]
+    fn last_buy_charges_exact_cost_of_remaining_tokens() {
+        // (virtual sol, virtual tokens, real tokens) for a few non-default launches
+        let configs = [
+            (30_000_000_000u64, 1_073_000_000_000_000u64, 793_100_000_000_000u64),
+            (5_000_000_000, 2_000_000_000, 1_500_000_000),
+            (1_000_000_000, 1_000_000_000_000_000, 999_000_000_000_000),
+        ];
+        for (virtual_sol_reserves, virtual_token_reserves, real_token_reserves) in configs {
+            let mut curve = BondingCurve {
+                virtual_sol_reserves,
+                virtual_token_reserves,
+                real_token_reserves,
+                initial_real_token_reserves: real_token_reserves,
+                token_total_supply: real_token_reserves,
+                ..BondingCurve::default()
+            };
+            let exact_cost = curve.get_sol_for_buy_tokens(real_token_reserves).unwrap();
+            // One lamport less does not buy every remaining token
+            assert!(curve.get_tokens_for_buy_sol(exact_cost - 1).unwrap() < real_token_reserves);
+            assert!(curve.get_tokens_for_buy_sol(exact_cost).unwrap() >= real_token_reserves);
+
+            let requested = exact_cost.saturating_mul(3);
+            let result = curve.apply_buy(requested).unwrap();
+            assert_eq!(result.token_amount, real_token_reserves);
+            assert_eq!(result.sol_amount, exact_cost);
+            assert_eq!(result.requested_sol_amount, requested);
+            assert!(result.is_partial_fill());
+            assert!(curve.complete);
+            assert_eq!(curve.real_token_reserves, 0);
+            assert_eq!(curve.real_sol_reserves, exact_cost);
+        }
+    }
+
+    #[test]
+    fn exact_out_quotes_round_in_favor_of_the_curve() {
+        for mint_decimals in [0u8, 6, 9] {
+            let curve = curve_with_decimals(mint_decimals);
+            let unit = (10u64).pow(mint_decimals as u32);
+            for tokens_out in [1, unit, 12_345 * unit, 100_000_000 * unit] {
+                let sol_in = curve.get_sol_for_buy_tokens(tokens_out).unwrap();
+                // Paying the quote gets at least the tokens, one lamport less does not
+                assert!(curve.get_tokens_for_buy_sol(sol_in).unwrap() >= tokens_out);
+                if sol_in > 1 {
+                    assert!(curve.get_tokens_for_buy_sol(sol_in - 1).unwrap() < tokens_out);
+                }
+            }
+            for sol_out in [1u64, 1_000_000, 2_000_000_000] {
+                let tokens_in = curve.get_tokens_for_sell_sol(sol_out).unwrap();
+                assert!(curve.get_sol_for_sell_tokens(tokens_in).unwrap() >= sol_out);
+                if tokens_in > 1 {
+                    assert!(curve.get_sol_for_sell_tokens(tokens_in - 1).unwrap() < sol_out);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn exact_out_swaps_keep_k() {
+        for mint_decimals in [0u8, 6, 9] {
+            let mut curve = curve_with_decimals(mint_decimals);
+            let unit = (10u64).pow(mint_decimals as u32);
+            let bought = curve.apply_buy_exact_out(1_000_000 * unit).unwrap();
+            assert_eq!(bought.token_amount, 1_000_000 * unit);
+            assert!(!bought.is_partial_fill());
+
+            let k_before = k(&curve);
+            let sold = curve.apply_sell_exact_out(bought.sol_amount / 2).unwrap();
+            assert_eq!(sold.sol_amount, bought.sol_amount / 2);
+            assert!(k(&curve) >= k_before, "d={}", mint_decimals);
+
+            // More tokens than the curve holds is rejected rather than filled
+            assert_eq!(
+                curve.apply_buy_exact_out(curve.real_token_reserves + 1).unwrap_err(),
+                ContractError::InsufficientCurveReserves.into()
+            );
+        }
+    }
+
+    #[test]
+    fn gross_for_net_after_fee_leaves_exact_net() {
+        let clock = clock_at(1_000, 0);
+        for fee_bps in [0u16, 1, 100, 9_900] {
+            let curve = BondingCurve {
+                start_slot: 1_000,
+                fee_schedule: FeeSchedule {
+                    breakpoints: vec![FeeBreakpoint { elapsed: 0, fee_bps }],
+                },
+                ..BondingCurve::default()
+            };
+            for net in [1u64, 999, 1_000_000_007] {
+                let (gross, fee) = curve.gross_for_net_after_fee(net, &clock).unwrap();
+                assert_eq!(gross - fee, net);
+                assert!(fee >= curve.calculate_fee(gross, &clock).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn hard_cap_fills_final_buy_up_to_target() {
+        let sol_raise_target = 10_000_000_000;
+        let mut curve = BondingCurve {
+            sol_raise_target,
+            real_sol_reserves: 9_000_000_000,
+            hard_cap: true,
+            ..curve_with_decimals(6)
+        };
+        let result = curve.apply_buy(5_000_000_000).unwrap();
+        assert_eq!(result.sol_amount, 1_000_000_000);
+        assert_eq!(result.requested_sol_amount, 5_000_000_000);
+        assert!(result.is_partial_fill());
+        assert_eq!(curve.real_sol_reserves, sol_raise_target);
+        assert!(curve.complete);
+
+        // Exact output buys past the cap are rejected instead of trimmed
+        let mut curve = BondingCurve {
+            sol_raise_target,
+            real_sol_reserves: 9_000_000_000,
+            hard_cap: true,
+            ..curve_with_decimals(6)
+        };
+        let tokens = curve.get_tokens_for_buy_sol(2_000_000_000).unwrap();
+        assert_eq!(
+            curve.apply_buy_exact_out(tokens).unwrap_err(),
+            ContractError::HardCapExceeded.into()
+        );
+
+        // Without the cap the raise overshoots
+        let mut curve = BondingCurve {
+            sol_raise_target,
+            real_sol_reserves: 9_000_000_000,
+            ..curve_with_decimals(6)
+        };
+        let result = curve.apply_buy(5_000_000_000).unwrap();
+        assert!(!result.is_partial_fill());
+        assert_eq!(curve.real_sol_reserves, 14_000_000_000);
+        assert!(curve.complete);
+    }
+
+    #[test]
+    fn surplus_tolerates_donations_but_not_shortfalls() {
+        let rent = 2_000_000;
+        let curve = BondingCurve {
+            real_sol_reserves: 5_000,
+            real_token_reserves: 700,
+            initial_real_token_reserves: 800,
+            token_total_supply: 1_000,
+            ..BondingCurve::default()
+        };
+        // 200 reserved tokens sit in the ATA next to the 700 tradable ones
+        assert_eq!(curve.surplus(rent + 5_000, rent, 900).unwrap(), (0, 0));
+        assert_eq!(curve.surplus(rent + 5_001, rent, 950).unwrap(), (1, 50));
+        assert!(curve.surplus(rent + 4_999, rent, 900).is_err());
+        assert!(curve.surplus(rent + 5_000, rent, 899).is_err());
+
+        // Nothing is reserved once migrated
+        let migrated = BondingCurve { migrated: true, real_sol_reserves: 0, real_token_reserves: 0, ..curve };
+        assert_eq!(migrated.surplus(rent + 10, rent, 3).unwrap(), (10, 3));
+    }
+
+    #[test]
+    fn expiry_only_applies_to_incomplete_curves_with_a_deadline() {
+        let mut curve = BondingCurve { end_time: Some(1_700_000_000), ..BondingCurve::default() };
+        assert!(!curve.is_expired(&clock_at(0, 1_699_999_999)));
+        assert!(curve.is_expired(&clock_at(0, 1_700_000_000)));
+
+        curve.complete = true;
+        assert!(!curve.is_expired(&clock_at(0, 1_800_000_000)));
+
+        let open_ended = BondingCurve::default();
+        assert!(!open_ended.is_expired(&clock_at(0, i64::MAX)));
+    }
+
+    #[test]
+    fn refunds_are_pro_rata_and_order_independent() {
+        let mut curve = BondingCurve {
+            initial_real_token_reserves: 1_000,
+            real_token_reserves: 700,
+            real_sol_reserves: 1_000_003,
+            ..BondingCurve::default()
+        };
+        // 300 tokens circulate, so each third is worth a third of the SOL
+        let first = curve.apply_refund(100).unwrap();
+        let second = curve.apply_refund(100).unwrap();
+        let last = curve.apply_refund(100).unwrap();
+        assert_eq!(first, 333_334);
+        assert!(second >= first && last >= second);
+        assert_eq!(first + second + last, 1_000_003);
+        assert_eq!(curve.real_sol_reserves, 0);
+        assert_eq!(curve.real_token_reserves, 1_000);
+
+        // Can't return more than was ever bought
+        assert_eq!(curve.apply_refund(1).unwrap_err(), ContractError::RefundFailed.into());
+        assert_eq!(curve.apply_refund(0).unwrap_err(), ContractError::MinSwap.into());
+
+        // Reserves that no longer add up surface as an arithmetic error
+        curve.real_token_reserves = 1_001;
+        assert_eq!(curve.apply_refund(1).unwrap_err(), ContractError::ArithmeticError.into());
+    }
+
+    #[test]
+    fn market_cap_target_completes_curve() {
+        let mut curve = curve_with_decimals(6);
+        let starting_market_cap = curve.market_cap_lamports().unwrap();
+        // 1.073B virtual tokens against a 1B supply at 30 SOL of virtual reserves
+        assert_eq!(starting_market_cap, 27_958_993_476);
+
+        curve.market_cap_target_lamports = Some(starting_market_cap * 2);
+        curve.apply_buy(1_000_000_000).unwrap();
+        assert!(!curve.complete);
+        assert_eq!(curve.completion_reason, None);
+
+        curve.apply_buy(20_000_000_000).unwrap();
+        assert!(curve.market_cap_lamports().unwrap() >= starting_market_cap * 2);
+        assert!(curve.complete);
+        assert_eq!(curve.completion_reason, Some(CompletionReason::MarketCapTarget));
+    }
+
+    #[test]
+    fn first_completion_trigger_is_the_reason() {
+        let mut curve = BondingCurve {
+            sol_raise_target: 1_000_000_000,
+            market_cap_target_lamports: Some(1),
+            ..curve_with_decimals(6)
+        };
+        curve.apply_buy(2_000_000_000).unwrap();
+        assert_eq!(curve.completion_reason, Some(CompletionReason::SolRaiseTarget));
+
+        let mut curve = curve_with_decimals(6);
+        curve.initial_real_token_reserves = curve.real_token_reserves;
+        curve.apply_buy(u64::MAX / 4).unwrap();
+        assert_eq!(curve.completion_reason, Some(CompletionReason::TokensSoldOut));
+    }
+}
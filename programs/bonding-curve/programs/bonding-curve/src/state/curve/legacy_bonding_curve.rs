@@ -0,0 +1,173 @@
+use anchor_lang::{ prelude::*, Discriminator };
+
+use crate::{ errors::ContractError, BondingCurve, FeeSchedule };
+
+/// `BondingCurve` as laid out before the SOL vault, in the original program release.
+/// Curves created back then are still this size, so they don't deserialize as
+/// `BondingCurve` until `migrate_sol_vault` reallocates them.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Default)]
+pub struct LegacyBondingCurve {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub initial_virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub start_time: i64,
+    pub complete: bool,
+    pub bump: u8,
+    pub sol_raise_target: u64,
+    pub realm_pubkey: Pubkey,
+}
+
+impl LegacyBondingCurve {
+    /// Account size of a legacy curve, discriminator included
+    pub const ACCOUNT_SIZE: usize = 8 + Self::INIT_SPACE;
+
+    /// Decodes the data of a legacy curve account. Accounts already at the current
+    /// size have been migrated.
+    pub fn try_decode(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() != 8 + BondingCurve::INIT_SPACE,
+            ContractError::SolVaultAlreadyMigrated
+        );
+        require!(data.len() == Self::ACCOUNT_SIZE, ContractError::InvalidArgument);
+        require!(
+            data.starts_with(BondingCurve::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        Self::deserialize(&mut &data[8..]).map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+    }
+
+    /// The curve in the current layout. Fields the legacy layout lacks are rebuilt
+    /// from the reserves, the mint and the clock, or take their defaults.
+    pub fn into_bonding_curve(self, mint_decimals: u8, clock: &Clock) -> BondingCurve {
+        // Buys and sells always moved the virtual and real token reserves together
+        let tokens_sold = self.initial_virtual_token_reserves.saturating_sub(
+            self.virtual_token_reserves
+        );
+        BondingCurve {
+            mint: self.mint,
+            creator: self.creator,
+            initial_virtual_token_reserves: self.initial_virtual_token_reserves,
+            initial_real_token_reserves: self.real_token_reserves.saturating_add(tokens_sold),
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+            real_sol_reserves: self.real_sol_reserves,
+            real_token_reserves: self.real_token_reserves,
+            token_total_supply: self.token_total_supply,
+            mint_decimals,
+            start_time: self.start_time,
+            start_slot: Self::start_slot(self.start_time, clock),
+            complete: self.complete,
+            bump: self.bump,
+            sol_raise_target: self.sol_raise_target,
+            realm_pubkey: self.realm_pubkey,
+            // Legacy curves charged the fee the default schedule reproduces
+            fee_schedule: FeeSchedule::default(),
+            ..Default::default()
+        }
+    }
+
+    /// Slot of `start_time`, approximated from the current clock like
+    /// `BondingCurve::start_slot_from_params` does for new curves
+    fn start_slot(start_time: i64, clock: &Clock) -> u64 {
+        let slots_between = |seconds: i64| {
+            (seconds.max(0) as u64).saturating_mul(1_000).div_ceil(BondingCurve::MS_PER_SLOT)
+        };
+        if start_time > clock.unix_timestamp {
+            clock.slot.saturating_add(slots_between(start_time.saturating_sub(clock.unix_timestamp)))
+        } else {
+            clock.slot.saturating_sub(slots_between(clock.unix_timestamp.saturating_sub(start_time)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy_account_data(legacy: &LegacyBondingCurve) -> Vec<u8> {
+        let mut data = BondingCurve::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        data
+    }
+
+    fn clock(slot: u64, unix_timestamp: i64) -> Clock {
+        Clock { slot, unix_timestamp, ..Default::default() }
+    }
+
+    #[test]
+    fn decodes_legacy_layout_into_current_curve() {
+        let legacy = LegacyBondingCurve {
+            mint: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            initial_virtual_token_reserves: 100_000_000_000_000,
+            virtual_sol_reserves: 40_000_000_000,
+            virtual_token_reserves: 75_000_000_000_000,
+            real_sol_reserves: 42_000_000_000,
+            real_token_reserves: 25_000_000_000_000,
+            token_total_supply: 100_000_000_000_000,
+            start_time: 1_000,
+            complete: true,
+            bump: 254,
+            sol_raise_target: 85_000_000_000,
+            realm_pubkey: Pubkey::new_unique(),
+        };
+        let data = legacy_account_data(&legacy);
+        assert_eq!(data.len(), LegacyBondingCurve::ACCOUNT_SIZE);
+        assert!(data.len() < 8 + BondingCurve::INIT_SPACE);
+        // The current layout reads past the end of a legacy account
+        assert!(BondingCurve::try_deserialize(&mut data.as_slice()).is_err());
+
+        // 100 seconds after the start, 250 slots at the target slot duration
+        let decoded = LegacyBondingCurve::try_decode(&data).unwrap();
+        let mut curve = decoded.into_bonding_curve(6, &clock(10_000, 1_100));
+        assert_eq!(curve.mint, legacy.mint);
+        assert_eq!(curve.creator, legacy.creator);
+        assert_eq!(curve.virtual_sol_reserves, legacy.virtual_sol_reserves);
+        assert_eq!(curve.real_sol_reserves, legacy.real_sol_reserves);
+        assert_eq!(curve.real_token_reserves, legacy.real_token_reserves);
+        assert_eq!(curve.sol_raise_target, legacy.sol_raise_target);
+        assert_eq!(curve.realm_pubkey, legacy.realm_pubkey);
+        assert_eq!(curve.bump, legacy.bump);
+        assert!(curve.complete);
+        // 25M tokens were sold out of the 50M the curve started with
+        assert_eq!(curve.initial_real_token_reserves, 50_000_000_000_000);
+        assert_eq!(curve.mint_decimals, 6);
+        assert_eq!(curve.start_time, 1_000);
+        assert_eq!(curve.start_slot, 9_750);
+        assert_eq!(curve.fee_schedule, FeeSchedule::default());
+        assert!(!curve.migrated);
+        assert!(!curve.hard_cap);
+        assert_eq!(curve.sol_vault_bump, None);
+        assert_eq!(curve.end_time, None);
+        assert_eq!(curve.launch_config, None);
+
+        // Written back after the realloc, it reads as a current curve
+        curve.sol_vault_bump = Some(253);
+        let mut resized = vec![0; 8 + BondingCurve::INIT_SPACE];
+        curve.try_serialize(&mut resized.as_mut_slice()).unwrap();
+        let migrated = BondingCurve::try_deserialize(&mut resized.as_slice()).unwrap();
+        assert_eq!(migrated.real_sol_reserves, legacy.real_sol_reserves);
+        assert_eq!(migrated.sol_vault_bump, Some(253));
+        assert!(LegacyBondingCurve::try_decode(&resized).is_err());
+    }
+
+    #[test]
+    fn places_future_starts_ahead_of_the_clock() {
+        let legacy = LegacyBondingCurve { start_time: 1_100, ..Default::default() };
+        let curve = legacy.into_bonding_curve(6, &clock(10_000, 1_000));
+        assert_eq!(curve.start_slot, 10_250);
+    }
+
+    #[test]
+    fn rejects_other_accounts() {
+        let mut data = legacy_account_data(&LegacyBondingCurve::default());
+        data[0] ^= 1;
+        assert!(LegacyBondingCurve::try_decode(&data).is_err());
+        assert!(LegacyBondingCurve::try_decode(&[0; 16]).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{ prelude::*, system_program::{ transfer, Transfer } };
 use anchor_spl::{
     token_2022::spl_token_2022::instruction::AuthorityType,
     token_interface::{
@@ -13,7 +13,7 @@ use anchor_spl::{
         SetAuthority,
     },
 };
-use crate::{ BondingCurve, Global };
+use crate::{ errors::ContractError, BondingCurve, Global };
 pub struct BondingCurveLockerCtx<'info> {
     pub bonding_curve_bump: u8,
     pub mint: Box<InterfaceAccount<'info, Mint>>,
@@ -21,17 +21,19 @@ pub struct BondingCurveLockerCtx<'info> {
     pub bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     pub token_program: Interface<'info, TokenInterface>,
     pub global: Box<Account<'info, Global>>,
+    pub sol_vault: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
 }
 
-impl BondingCurveLockerCtx<'_> {
-    fn get_signer<'a>(&self) -> [&[u8]; 3] {
+impl<'info> BondingCurveLockerCtx<'info> {
+    fn get_signer(&self) -> [&[u8]; 3] {
         let signer: [&[u8]; 3] = BondingCurve::get_signer(
             &self.bonding_curve_bump,
             self.mint.to_account_info().key
         );
         signer
     }
-    pub fn lock_ata<'a>(&self) -> Result<()> {
+    pub fn lock_ata(&self) -> Result<()> {
         let signer = self.get_signer();
         let signer_seeds: &[&[&[u8]]; 1] = &[&signer[..]];
 
@@ -47,7 +49,7 @@ impl BondingCurveLockerCtx<'_> {
 
         Ok(())
     }
-    pub fn unlock_ata<'a>(&self) -> Result<()> {
+    pub fn unlock_ata(&self) -> Result<()> {
         let signer = self.get_signer();
         let signer_seeds: &[&[&[u8]]; 1] = &[&signer[..]];
 
@@ -64,6 +66,32 @@ impl BondingCurveLockerCtx<'_> {
         Ok(())
     }
 
+    /// Moves pool SOL out of the system owned vault, signing for the vault PDA
+    pub fn withdraw_sol(&self, to: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let bonding_curve_key = self.bonding_curve.key();
+        let vault_bump = self.bonding_curve.sol_vault_bump.ok_or(ContractError::SolVaultNotMigrated)?;
+        let signer = BondingCurve::get_sol_vault_signer(&bonding_curve_key, &vault_bump);
+        let signer_seeds: &[&[&[u8]]; 1] = &[&signer[..]];
+
+        transfer(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.sol_vault.to_account_info(),
+                    to: to.clone(),
+                },
+                signer_seeds
+            ),
+            amount
+        )?;
+        msg!("BondingCurveLockerCtx::withdraw_sol: {} lamports", amount);
+
+        Ok(())
+    }
+
     pub fn revoke_mint_authority(&self) -> Result<()> {
         let mint_info = self.mint.to_account_info();
         let mint_authority_info = self.bonding_curve.to_account_info();
@@ -115,5 +143,6 @@ impl BondingCurveLockerCtx<'_> {
 }
 
 pub trait IntoBondingCurveLockerCtx<'info> {
+    #[allow(clippy::wrong_self_convention)]
     fn into_bonding_curve_locker_ctx(&self, bonding_curve_bump: u8) -> BondingCurveLockerCtx<'info>;
 }
@@ -1,4 +1,6 @@
 mod bonding_curve;
+mod legacy_bonding_curve;
 mod locker;
 pub use bonding_curve::*;
+pub use legacy_bonding_curve::*;
 pub use locker::*;
\ No newline at end of file
@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+
+use crate::{ errors::ContractError, math::{ self, Rounding } };
+
+/// A point on the fee schedule: from `elapsed` onwards the fee moves linearly
+/// towards the next breakpoint, and stays flat after the last one.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Debug, Default, PartialEq)]
+pub struct FeeBreakpoint {
+    /// Slots elapsed since the curve started
+    pub elapsed: u64,
+    /// Fee in basis points at `elapsed`
+    pub fee_bps: u16,
+}
+
+/// Piecewise linear trading fee, used for anti-snipe protection right after launch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, Debug, PartialEq)]
+pub struct FeeSchedule {
+    #[max_len(8)]
+    pub breakpoints: Vec<FeeBreakpoint>,
+}
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        // The original launch fee: 99% for the first 150 slots, then a drop to 9.17%
+        // decaying linearly to 0.87% at slot 250, and a flat 1% from slot 251
+        Self {
+            breakpoints: vec![
+                FeeBreakpoint { elapsed: 0, fee_bps: 9_900 },
+                FeeBreakpoint { elapsed: 149, fee_bps: 9_900 },
+                FeeBreakpoint { elapsed: 150, fee_bps: 917 },
+                FeeBreakpoint { elapsed: 250, fee_bps: 87 },
+                FeeBreakpoint { elapsed: 251, fee_bps: 100 }
+            ],
+        }
+    }
+}
+
+impl FeeSchedule {
+    pub const MAX_BREAKPOINTS: usize = 8;
+    /// A 100% fee would leave nothing to trade, and no gross amount nets out after it
+    pub const MAX_FEE_BPS: u16 = 9_999;
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            !self.breakpoints.is_empty() && self.breakpoints.len() <= Self::MAX_BREAKPOINTS,
+            ContractError::InvalidFeeSchedule
+        );
+        for breakpoint in self.breakpoints.iter() {
+            require!(breakpoint.fee_bps <= Self::MAX_FEE_BPS, ContractError::InvalidFeeSchedule);
+        }
+        for pair in self.breakpoints.windows(2) {
+            require!(pair[0].elapsed < pair[1].elapsed, ContractError::InvalidFeeSchedule);
+        }
+        Ok(())
+    }
+
+    /// Fee in basis points after `elapsed` slots, interpolated between breakpoints
+    pub fn fee_bps(&self, elapsed: u64) -> Result<u64> {
+        let first = self.breakpoints.first().ok_or(ContractError::InvalidFeeSchedule)?;
+        if elapsed <= first.elapsed {
+            return Ok(first.fee_bps as u64);
+        }
+        for pair in self.breakpoints.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+            if elapsed >= end.elapsed {
+                continue;
+            }
+            let span = end.elapsed
+                .checked_sub(start.elapsed)
+                .filter(|span| *span > 0)
+                .ok_or(ContractError::InvalidFeeSchedule)?;
+            let progress = math::checked_sub(elapsed, start.elapsed)?;
+            let start_fee = start.fee_bps as u64;
+            let end_fee = end.fee_bps as u64;
+            // Rounds towards the start fee, so a decaying fee is rounded up
+            let fee_bps = if end_fee >= start_fee {
+                let fee_change = math::checked_sub(end_fee, start_fee)?;
+                let step = math::mul_div(fee_change, progress, span, Rounding::Down)?;
+                math::checked_add(start_fee, step)?
+            } else {
+                let fee_change = math::checked_sub(start_fee, end_fee)?;
+                let step = math::mul_div(fee_change, progress, span, Rounding::Down)?;
+                math::checked_sub(start_fee, step)?
+            };
+            return Ok(fee_bps);
+        }
+        let last = self.breakpoints.last().ok_or(ContractError::InvalidFeeSchedule)?;
+        Ok(last.fee_bps as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(points: &[(u64, u16)]) -> FeeSchedule {
+        FeeSchedule {
+            breakpoints: points
+                .iter()
+                .map(|(elapsed, fee_bps)| FeeBreakpoint { elapsed: *elapsed, fee_bps: *fee_bps })
+                .collect(),
+        }
+    }
+
+    /// Fee of the original hardcoded `calculate_fee`, by slots since launch
+    fn baseline_fee_bps(slots_passed: i64) -> u64 {
+        if slots_passed < 150 {
+            9_900
+        } else if slots_passed <= 250 {
+            ((-8_300_000 * slots_passed + 2_162_600_000) / 1_000_000) as u64
+        } else {
+            100
+        }
+    }
+
+    #[test]
+    fn default_schedule_phases() {
+        let fees = FeeSchedule::default();
+        assert!(fees.validate().is_ok());
+        assert_eq!(fees.fee_bps(0).unwrap(), 9_900);
+        assert_eq!(fees.fee_bps(149).unwrap(), 9_900);
+        assert_eq!(fees.fee_bps(150).unwrap(), 917);
+        assert_eq!(fees.fee_bps(200).unwrap(), 502);
+        assert_eq!(fees.fee_bps(250).unwrap(), 87);
+        assert_eq!(fees.fee_bps(251).unwrap(), 100);
+        assert_eq!(fees.fee_bps(u64::MAX).unwrap(), 100);
+    }
+
+    #[test]
+    fn default_schedule_matches_baseline_formula() {
+        let fees = FeeSchedule::default();
+        let slots = [0, 1, 75, 148, 149, 150, 151, 175, 199, 200, 226, 249, 250, 251, 10_000];
+        for slots_passed in slots {
+            let fee_bps = fees.fee_bps(slots_passed as u64).unwrap();
+            let baseline = baseline_fee_bps(slots_passed);
+            // The old formula decays from 917.6 bps, whole bps breakpoints round the
+            // fee up by at most one
+            assert!(
+                fee_bps == baseline || fee_bps == baseline + 1,
+                "slot +{}: {} vs {}",
+                slots_passed,
+                fee_bps,
+                baseline
+            );
+        }
+        for slots_passed in [0, 149, 150, 200, 250, 251] {
+            assert_eq!(
+                fees.fee_bps(slots_passed as u64).unwrap(),
+                baseline_fee_bps(slots_passed)
+            );
+        }
+    }
+
+    #[test]
+    fn interpolates_increasing_fees() {
+        let fees = schedule(&[(10, 0), (20, 1_000)]);
+        assert_eq!(fees.fee_bps(0).unwrap(), 0);
+        assert_eq!(fees.fee_bps(15).unwrap(), 500);
+        assert_eq!(fees.fee_bps(25).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn rejects_invalid_schedules() {
+        assert!(schedule(&[]).validate().is_err());
+        assert!(schedule(&[(0, 10_001)]).validate().is_err());
+        assert!(schedule(&[(0, 10_000)]).validate().is_err());
+        assert!(schedule(&[(0, 100), (0, 50)]).validate().is_err());
+        assert!(schedule(&[(10, 100), (5, 50)]).validate().is_err());
+        let too_long: Vec<(u64, u16)> = (0..9).map(|i| (i, 100)).collect();
+        assert!(schedule(&too_long).validate().is_err());
+        assert!(schedule(&[(0, 9_999)]).validate().is_ok());
+    }
+}
@@ -1,5 +1,7 @@
 pub use anchor_lang::prelude::*;
 
+use crate::{ errors::ContractError, FeeSchedule, LaunchConfigParams, Roles };
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Debug, PartialEq)]
 pub enum ProgramStatus {
     Running,
@@ -8,6 +10,45 @@ pub enum ProgramStatus {
     Paused,
 }
 
+/// Instructions gated by `Global.status`. Admin instructions are never gated so a
+/// paused program can always be resumed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProgramAction {
+    CreateBondingCurve,
+    Swap,
+    MigrateLiquidity,
+    Skim,
+    Refund,
+    MigrateSolVault,
+}
+
+impl ProgramStatus {
+    /// - `Running`: everything is allowed
+    /// - `SwapOnly`: trading and migration, but no new curves
+    /// - `SwapOnlyNoLaunch`: trading only, no new curves and no migration to liquidity pools
+    /// - `Paused`: nothing but admin instructions
+    ///
+    /// `Skim` only moves surplus nobody owns, `Refund` lets holders of failed launches
+    /// exit and `MigrateSolVault` only relocates pool SOL, all are allowed whenever the
+    /// program isn't paused
+    pub fn allows(&self, action: ProgramAction) -> bool {
+        match (self, action) {
+            (ProgramStatus::Running, _) => true,
+            (ProgramStatus::SwapOnly, ProgramAction::CreateBondingCurve) => false,
+            (ProgramStatus::SwapOnly, _) => true,
+            (
+                ProgramStatus::SwapOnlyNoLaunch,
+                ProgramAction::Swap |
+                ProgramAction::Skim |
+                ProgramAction::Refund |
+                ProgramAction::MigrateSolVault,
+            ) => true,
+            (ProgramStatus::SwapOnlyNoLaunch, _) => false,
+            (ProgramStatus::Paused, _) => false,
+        }
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Global {
@@ -23,6 +64,9 @@ pub struct Global {
     pub mint_decimals: u8,
     pub whitelist_enabled: bool,
     pub bump: u8,
+    pub fee_schedule: FeeSchedule,
+    /// Proposed by the global authority, takes over once it accepts
+    pub pending_authority: Option<Pubkey>,
 }
 
 impl Default for Global {
@@ -41,6 +85,8 @@ impl Default for Global {
             mint_decimals: 6,
             whitelist_enabled: false,
             bump: 0,
+            fee_schedule: FeeSchedule::default(),
+            pending_authority: None,
         }
     }
 }
@@ -81,6 +127,72 @@ impl Global {
         if let Some(whitelist_enabled) = params.whitelist_enabled {
             self.whitelist_enabled = whitelist_enabled;
         }
+        if let Some(fee_schedule) = params.fee_schedule {
+            self.fee_schedule = fee_schedule;
+        }
+    }
+
+    pub fn require_status(&self, action: ProgramAction) -> Result<()> {
+        if !self.status.allows(action) {
+            msg!("{:?} is not allowed while the program status is {:?}", action, self.status);
+            return Err(ContractError::ProgramNotRunning.into());
+        }
+        Ok(())
+    }
+
+    /// `signer` is the global authority, or `permissions` of its `Roles` account include `required`
+    pub fn require_roles(
+        &self,
+        signer: &Pubkey,
+        permissions: Option<u8>,
+        required: u8
+    ) -> Result<()> {
+        if *signer == self.global_authority {
+            return Ok(());
+        }
+        let Some(permissions) = permissions else {
+            return Err(ContractError::InvalidGlobalAuthority.into());
+        };
+        if permissions & required != required {
+            msg!("Signer has roles {:#05b}, needs {:#05b}", permissions, required);
+            return Err(ContractError::MissingRole.into());
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.launch_config_params().validate()?;
+        require!(self.fee_receiver != Pubkey::default(), ContractError::InvalidFeeReceiver);
+        Ok(())
+    }
+
+    /// Launch parameters of curves created without a `LaunchConfig`, any raise target goes
+    pub fn launch_config_params(&self) -> LaunchConfigParams {
+        LaunchConfigParams {
+            initial_virtual_token_reserves: self.initial_virtual_token_reserves,
+            initial_virtual_sol_reserves: self.initial_virtual_sol_reserves,
+            initial_real_token_reserves: self.initial_real_token_reserves,
+            token_total_supply: self.token_total_supply,
+            mint_decimals: self.mint_decimals,
+            fee_schedule: self.fee_schedule.clone(),
+            min_sol_raise_target: 0,
+            max_sol_raise_target: u64::MAX,
+        }
+    }
+
+    pub fn settings(&self) -> GlobalSettings {
+        GlobalSettings {
+            status: self.status,
+            initial_virtual_token_reserves: self.initial_virtual_token_reserves,
+            initial_virtual_sol_reserves: self.initial_virtual_sol_reserves,
+            initial_real_token_reserves: self.initial_real_token_reserves,
+            token_total_supply: self.token_total_supply,
+            mint_decimals: self.mint_decimals,
+            migrate_fee_amount: self.migrate_fee_amount,
+            fee_receiver: self.fee_receiver,
+            whitelist_enabled: self.whitelist_enabled,
+            fee_schedule: self.fee_schedule.clone(),
+        }
     }
 }
 
@@ -95,4 +207,153 @@ pub struct GlobalSettingsInput {
     pub fee_receiver: Option<Pubkey>,
     pub status: Option<ProgramStatus>,
     pub whitelist_enabled: Option<bool>,
+    pub fee_schedule: Option<FeeSchedule>,
+}
+
+impl GlobalSettingsInput {
+    /// Roles needed to apply these settings, none for an update that changes nothing
+    pub fn required_roles(&self) -> u8 {
+        let mut roles = 0;
+        match self.status {
+            Some(ProgramStatus::Paused) => {
+                roles |= Roles::PAUSER;
+            }
+            Some(_) => {
+                roles |= Roles::CONFIG_MANAGER;
+            }
+            None => {}
+        }
+        if
+            self.fee_receiver.is_some() ||
+            self.migrate_fee_amount.is_some() ||
+            self.fee_schedule.is_some()
+        {
+            roles |= Roles::FEE_MANAGER;
+        }
+        if
+            self.initial_virtual_token_reserves.is_some() ||
+            self.initial_virtual_sol_reserves.is_some() ||
+            self.initial_real_token_reserves.is_some() ||
+            self.token_total_supply.is_some() ||
+            self.mint_decimals.is_some() ||
+            self.whitelist_enabled.is_some()
+        {
+            roles |= Roles::CONFIG_MANAGER;
+        }
+        roles
+    }
+}
+
+/// Snapshot of the admin-tunable fields of `Global`, used in events
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct GlobalSettings {
+    pub status: ProgramStatus,
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub mint_decimals: u8,
+    pub migrate_fee_amount: u64,
+    pub fee_receiver: Pubkey,
+    pub whitelist_enabled: bool,
+    pub fee_schedule: FeeSchedule,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_action_matrix() {
+        use ProgramAction::*;
+        use ProgramStatus::*;
+
+        let cases = [
+            (Running, CreateBondingCurve, true),
+            (Running, Swap, true),
+            (Running, MigrateLiquidity, true),
+            (Running, Skim, true),
+            (Running, Refund, true),
+            (Running, MigrateSolVault, true),
+            (SwapOnly, CreateBondingCurve, false),
+            (SwapOnly, Swap, true),
+            (SwapOnly, MigrateLiquidity, true),
+            (SwapOnly, Skim, true),
+            (SwapOnly, Refund, true),
+            (SwapOnly, MigrateSolVault, true),
+            (SwapOnlyNoLaunch, CreateBondingCurve, false),
+            (SwapOnlyNoLaunch, Swap, true),
+            (SwapOnlyNoLaunch, MigrateLiquidity, false),
+            (SwapOnlyNoLaunch, Skim, true),
+            (SwapOnlyNoLaunch, Refund, true),
+            (SwapOnlyNoLaunch, MigrateSolVault, true),
+            (Paused, CreateBondingCurve, false),
+            (Paused, Swap, false),
+            (Paused, MigrateLiquidity, false),
+            (Paused, Skim, false),
+            (Paused, Refund, false),
+            (Paused, MigrateSolVault, false),
+        ];
+        for (status, action, allowed) in cases {
+            assert_eq!(status.allows(action), allowed, "{:?} / {:?}", status, action);
+
+            let global = Global { status, ..Global::default() };
+            assert_eq!(global.require_status(action).is_ok(), allowed);
+        }
+    }
+
+    fn settings() -> GlobalSettingsInput {
+        GlobalSettingsInput {
+            initial_virtual_token_reserves: None,
+            initial_virtual_sol_reserves: None,
+            initial_real_token_reserves: None,
+            token_total_supply: None,
+            mint_decimals: None,
+            migrate_fee_amount: None,
+            fee_receiver: None,
+            status: None,
+            whitelist_enabled: None,
+            fee_schedule: None,
+        }
+    }
+
+    #[test]
+    fn settings_require_matching_roles() {
+        assert_eq!(settings().required_roles(), 0);
+
+        let pause = GlobalSettingsInput { status: Some(ProgramStatus::Paused), ..settings() };
+        assert_eq!(pause.required_roles(), Roles::PAUSER);
+
+        let resume = GlobalSettingsInput { status: Some(ProgramStatus::Running), ..settings() };
+        assert_eq!(resume.required_roles(), Roles::CONFIG_MANAGER);
+
+        let fee_receiver = GlobalSettingsInput {
+            fee_receiver: Some(Pubkey::new_unique()),
+            ..settings()
+        };
+        assert_eq!(fee_receiver.required_roles(), Roles::FEE_MANAGER);
+
+        let mixed = GlobalSettingsInput {
+            mint_decimals: Some(9),
+            ..fee_receiver
+        };
+        assert_eq!(mixed.required_roles(), Roles::FEE_MANAGER | Roles::CONFIG_MANAGER);
+    }
+
+    #[test]
+    fn global_authority_holds_every_role() {
+        let authority = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let global = Global { global_authority: authority, ..Global::default() };
+
+        assert!(global.require_roles(&authority, None, Roles::ALL).is_ok());
+        assert!(global.require_roles(&member, Some(Roles::PAUSER), Roles::PAUSER).is_ok());
+        assert!(global.require_roles(&member, Some(Roles::PAUSER), Roles::FEE_MANAGER).is_err());
+        assert!(
+            global
+                .require_roles(&member, Some(Roles::PAUSER), Roles::PAUSER | Roles::FEE_MANAGER)
+                .is_err()
+        );
+        assert!(global.require_roles(&member, None, Roles::PAUSER).is_err());
+    }
 }
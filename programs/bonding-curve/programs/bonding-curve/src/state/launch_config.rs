@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::{ errors::ContractError, FeeSchedule };
+
+/// Launch profile created by the global authority, one PDA per `id`. Curves
+/// created from it copy its parameters and keep its key in `BondingCurve.launch_config`.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct LaunchConfig {
+    pub id: u64,
+    pub params: LaunchConfigParams,
+    pub bump: u8,
+}
+
+impl LaunchConfig {
+    pub const SEED_PREFIX: &'static str = "launch_config";
+}
+
+/// What a new curve is created with. `Global` provides these for curves
+/// launched without a launch config.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, Debug, PartialEq)]
+pub struct LaunchConfigParams {
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    /// Tradable part of `token_total_supply`, the rest goes to the treasury on migration
+    pub initial_real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub mint_decimals: u8,
+    /// Used unless the creator passes their own
+    pub fee_schedule: FeeSchedule,
+    /// Bounds on `CreateBondingCurveParams.sol_raise_target`, inclusive
+    pub min_sol_raise_target: u64,
+    pub max_sol_raise_target: u64,
+}
+
+impl LaunchConfigParams {
+    pub fn validate(&self) -> Result<()> {
+        require_gt!(self.token_total_supply, 0, ContractError::InvalidArgument);
+        require_gt!(self.initial_virtual_sol_reserves, 0, ContractError::InvalidArgument);
+        require_gt!(self.initial_real_token_reserves, 0, ContractError::InvalidArgument);
+        // Only part of the supply is tradable on the curve
+        require_gte!(
+            self.token_total_supply,
+            self.initial_real_token_reserves,
+            ContractError::InvalidArgument
+        );
+        require_gte!(
+            self.initial_virtual_token_reserves,
+            self.initial_real_token_reserves,
+            ContractError::InvalidArgument
+        );
+        require_gte!(
+            self.max_sol_raise_target,
+            self.min_sol_raise_target,
+            ContractError::InvalidArgument
+        );
+        self.fee_schedule.validate()?;
+        Ok(())
+    }
+
+    pub fn require_sol_raise_target(&self, sol_raise_target: u64) -> Result<()> {
+        require!(
+            (self.min_sol_raise_target..=self.max_sol_raise_target).contains(&sol_raise_target),
+            ContractError::InvalidSolRaiseTarget
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Global;
+
+    #[test]
+    fn validates_reserves_and_raise_limits() {
+        let params = LaunchConfigParams {
+            min_sol_raise_target: 10_000_000_000,
+            max_sol_raise_target: 100_000_000_000,
+            ..Global::default().launch_config_params()
+        };
+        assert!(params.validate().is_ok());
+        assert!(params.require_sol_raise_target(10_000_000_000).is_ok());
+        assert!(params.require_sol_raise_target(100_000_000_000).is_ok());
+        assert!(params.require_sol_raise_target(9_999_999_999).is_err());
+        assert!(params.require_sol_raise_target(100_000_000_001).is_err());
+
+        let inverted = LaunchConfigParams {
+            min_sol_raise_target: 100_000_000_001,
+            ..params.clone()
+        };
+        assert!(inverted.validate().is_err());
+
+        let oversold = LaunchConfigParams {
+            initial_real_token_reserves: params.token_total_supply + 1,
+            ..params.clone()
+        };
+        assert!(oversold.validate().is_err());
+
+        // Reserves are in base units, so whole token mints work like any other
+        let whole_tokens = LaunchConfigParams { mint_decimals: 0, ..params.clone() };
+        assert!(whole_tokens.validate().is_ok());
+    }
+
+    #[test]
+    fn global_defaults_allow_any_raise_target() {
+        let params = Global::default().launch_config_params();
+        assert!(params.require_sol_raise_target(0).is_ok());
+        assert!(params.require_sol_raise_target(u64::MAX).is_ok());
+    }
+}
@@ -0,0 +1,115 @@
+use anchor_lang::{ prelude::*, Discriminator };
+
+use crate::{ errors::ContractError, FeeSchedule, Global, ProgramStatus };
+
+/// `Global` as laid out in the original program release, before the fee schedule
+/// and the pending authority. It doesn't deserialize as `Global` until
+/// `migrate_global` reallocates it.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Debug)]
+pub struct LegacyGlobal {
+    pub status: ProgramStatus,
+    pub initialized: bool,
+    pub global_authority: Pubkey,
+    pub migrate_fee_amount: u64,
+    pub fee_receiver: Pubkey,
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub mint_decimals: u8,
+    pub whitelist_enabled: bool,
+    pub bump: u8,
+}
+
+impl LegacyGlobal {
+    /// Account size of the legacy global settings, discriminator included
+    pub const ACCOUNT_SIZE: usize = 8 + Self::INIT_SPACE;
+
+    /// Decodes the data of the legacy global settings account. Accounts already at
+    /// the current size have been migrated.
+    pub fn try_decode(data: &[u8]) -> Result<Self> {
+        require!(data.len() != 8 + Global::INIT_SPACE, ContractError::GlobalAlreadyMigrated);
+        require!(data.len() == Self::ACCOUNT_SIZE, ContractError::InvalidArgument);
+        require!(data.starts_with(Global::DISCRIMINATOR), ErrorCode::AccountDiscriminatorMismatch);
+        Self::deserialize(&mut &data[8..]).map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+impl From<LegacyGlobal> for Global {
+    fn from(legacy: LegacyGlobal) -> Self {
+        Self {
+            status: legacy.status,
+            initialized: legacy.initialized,
+            global_authority: legacy.global_authority,
+            migrate_fee_amount: legacy.migrate_fee_amount,
+            fee_receiver: legacy.fee_receiver,
+            initial_virtual_token_reserves: legacy.initial_virtual_token_reserves,
+            initial_virtual_sol_reserves: legacy.initial_virtual_sol_reserves,
+            initial_real_token_reserves: legacy.initial_real_token_reserves,
+            token_total_supply: legacy.token_total_supply,
+            mint_decimals: legacy.mint_decimals,
+            whitelist_enabled: legacy.whitelist_enabled,
+            bump: legacy.bump,
+            // The launch fee every curve charged before it was configurable
+            fee_schedule: FeeSchedule::default(),
+            pending_authority: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy_global() -> LegacyGlobal {
+        LegacyGlobal {
+            status: ProgramStatus::SwapOnly,
+            initialized: true,
+            global_authority: Pubkey::new_unique(),
+            migrate_fee_amount: 500,
+            fee_receiver: Pubkey::new_unique(),
+            initial_virtual_token_reserves: 100_000_000_000_000,
+            initial_virtual_sol_reserves: 30_000_000_000,
+            initial_real_token_reserves: 50_000_000_000_000,
+            token_total_supply: 100_000_000_000_000,
+            mint_decimals: 6,
+            whitelist_enabled: true,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn decodes_legacy_layout_into_current_global() {
+        let legacy = legacy_global();
+        let mut data = Global::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), LegacyGlobal::ACCOUNT_SIZE);
+        assert!(Global::try_deserialize(&mut data.as_slice()).is_err());
+
+        let global = Global::from(LegacyGlobal::try_decode(&data).unwrap());
+        assert_eq!(global.status, ProgramStatus::SwapOnly);
+        assert_eq!(global.global_authority, legacy.global_authority);
+        assert_eq!(global.fee_receiver, legacy.fee_receiver);
+        assert_eq!(global.initial_real_token_reserves, legacy.initial_real_token_reserves);
+        assert!(global.whitelist_enabled);
+        assert_eq!(global.bump, 255);
+        assert_eq!(global.fee_schedule, FeeSchedule::default());
+        assert_eq!(global.pending_authority, None);
+
+        // Written back after the realloc, it reads as the current layout
+        let mut resized = vec![0; 8 + Global::INIT_SPACE];
+        global.try_serialize(&mut resized.as_mut_slice()).unwrap();
+        let migrated = Global::try_deserialize(&mut resized.as_slice()).unwrap();
+        assert_eq!(migrated.global_authority, legacy.global_authority);
+        assert!(LegacyGlobal::try_decode(&resized).is_err());
+    }
+
+    #[test]
+    fn rejects_other_accounts() {
+        let mut data = Global::DISCRIMINATOR.to_vec();
+        legacy_global().serialize(&mut data).unwrap();
+        data[0] ^= 1;
+        assert!(LegacyGlobal::try_decode(&data).is_err());
+        assert!(LegacyGlobal::try_decode(&[0; 16]).is_err());
+    }
+}
@@ -1,5 +1,15 @@
 mod global;
 mod curve;
+mod fee_schedule;
+mod launch_config;
+mod legacy_global;
+mod roles;
+mod whitelist;
 
 pub use global::*;
-pub use curve::*;
\ No newline at end of file
+pub use curve::*;
+pub use fee_schedule::*;
+pub use launch_config::*;
+pub use legacy_global::*;
+pub use roles::*;
+pub use whitelist::*;
\ No newline at end of file
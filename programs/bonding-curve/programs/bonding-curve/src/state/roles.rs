@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ContractError;
+
+/// Admin permissions of `member`, one PDA per member. The global authority
+/// holds every role without one.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Roles {
+    pub member: Pubkey,
+    /// `Roles::PAUSER`, `Roles::FEE_MANAGER` and `Roles::CONFIG_MANAGER` bits
+    pub permissions: u8,
+    pub bump: u8,
+}
+
+impl Roles {
+    pub const SEED_PREFIX: &'static str = "roles";
+    /// Can set the program status to `Paused`, nothing else
+    pub const PAUSER: u8 = 1 << 0;
+    /// Fee receiver, migration fee and fee schedule
+    pub const FEE_MANAGER: u8 = 1 << 1;
+    /// Reserves, supply, decimals, any other status, the whitelist and launch configs
+    pub const CONFIG_MANAGER: u8 = 1 << 2;
+    pub const ALL: u8 = Self::PAUSER | Self::FEE_MANAGER | Self::CONFIG_MANAGER;
+
+    pub fn validate_permissions(permissions: u8) -> Result<()> {
+        require!(
+            permissions != 0 && permissions & !Self::ALL == 0,
+            ContractError::InvalidArgument
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permissions_must_be_known_roles() {
+        assert!(Roles::validate_permissions(Roles::PAUSER).is_ok());
+        assert!(Roles::validate_permissions(Roles::ALL).is_ok());
+        assert!(Roles::validate_permissions(0).is_err());
+        assert!(Roles::validate_permissions(1 << 3).is_err());
+    }
+}
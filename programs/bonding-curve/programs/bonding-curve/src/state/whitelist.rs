@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+/// Marks a creator as allowed to launch curves while `Global.whitelist_enabled` is set.
+/// One PDA per creator, closed again when the creator is removed.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Whitelist {
+    pub creator: Pubkey,
+    pub bump: u8,
+}
+
+impl Whitelist {
+    pub const SEED_PREFIX: &'static str = "whitelist";
+}
@@ -0,0 +1,211 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MINT_SIZE, MintLayout, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { BankrunProvider } from "anchor-bankrun";
+import { Clock, ProgramTestContext, startAnchor } from "solana-bankrun";
+import { BondingCurve } from "../target/types/bonding_curve";
+import IDL from "../target/idl/bonding_curve.json";
+import assert from "assert";
+
+// Launch timing and fee phases follow the slot, the unix timestamp only drives
+// `end_time`. Bankrun lets both clocks be warped independently of each other.
+describe("bonding-curve clock warps", () => {
+  const DAY = 86_400;
+  const amount = new anchor.BN(anchor.web3.LAMPORTS_PER_SOL);
+  const mint = anchor.web3.Keypair.generate().publicKey;
+
+  let context: ProgramTestContext;
+  let program: Program<BondingCurve>;
+  let globalAddress: anchor.web3.PublicKey;
+  let bondingCurve: anchor.web3.PublicKey;
+  let startSlot: bigint;
+  let startTime: bigint;
+
+  const warp = async (slot: bigint, unixTimestamp: bigint) => {
+    const clock = await context.banksClient.getClock();
+    context.setClock(
+      new Clock(
+        slot,
+        clock.epochStartTimestamp,
+        clock.epoch,
+        clock.leaderScheduleEpoch,
+        unixTimestamp
+      )
+    );
+  };
+
+  const quoteBuy = () =>
+    program.methods
+      .quote({ baseIn: false, amount, exactOut: false })
+      .accountsPartial({ global: globalAddress, mint, bondingCurve })
+      .view();
+
+  // Fee of a 1 SOL buy in bps of the amount
+  const buyFeeBps = async () =>
+    (await quoteBuy()).feeLamports.muln(10_000).div(amount).toNumber();
+
+  const expectError = async (action: Promise<unknown>, errorName: string) => {
+    try {
+      await action;
+    } catch (err) {
+      assert.ok(
+        err.toString().includes(errorName) ||
+          err.logs?.some((log: string) => log.includes(errorName)),
+        `Expected ${errorName}, got ${err}`
+      );
+      return;
+    }
+    assert.fail(`Expected ${errorName}`);
+  };
+
+  const setProgramAccount = async (
+    address: anchor.web3.PublicKey,
+    data: Buffer
+  ) =>
+    context.setAccount(address, {
+      lamports: 1_000_000_000,
+      data,
+      owner: program.programId,
+      executable: false,
+    });
+
+  before(async () => {
+    context = await startAnchor("", [], []);
+    const provider = new BankrunProvider(context);
+    program = new Program<BondingCurve>(IDL as BondingCurve, provider);
+
+    const clock = await context.banksClient.getClock();
+    startSlot = clock.slot + BigInt(1_000);
+    startTime = clock.unixTimestamp;
+
+    const [global, globalBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("global")],
+      program.programId
+    );
+    const [curve, curveBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("bonding_curve"), mint.toBuffer()],
+      program.programId
+    );
+    globalAddress = global;
+    bondingCurve = curve;
+
+    // What `create_bonding_curve` would leave behind, minus the metadata
+    // and token accounts the quote doesn't read
+    const defaultFees = {
+      breakpoints: [
+        [0, 9_900],
+        [149, 9_900],
+        [150, 917],
+        [250, 87],
+        [251, 100],
+      ].map(([elapsed, feeBps]) => ({
+        elapsed: new anchor.BN(elapsed),
+        feeBps,
+      })),
+    };
+    await setProgramAccount(
+      globalAddress,
+      await program.coder.accounts.encode("global", {
+        status: { running: {} },
+        initialized: true,
+        globalAuthority: context.payer.publicKey,
+        migrateFeeAmount: new anchor.BN(500),
+        feeReceiver: context.payer.publicKey,
+        initialVirtualTokenReserves: new anchor.BN(100_000_000_000_000),
+        initialVirtualSolReserves: new anchor.BN(30_000_000_000),
+        initialRealTokenReserves: new anchor.BN(50_000_000_000_000),
+        tokenTotalSupply: new anchor.BN(100_000_000_000_000),
+        mintDecimals: 6,
+        whitelistEnabled: false,
+        bump: globalBump,
+        feeSchedule: defaultFees,
+        pendingAuthority: null,
+      })
+    );
+    await setProgramAccount(
+      bondingCurve,
+      await program.coder.accounts.encode("bondingCurve", {
+        mint,
+        creator: context.payer.publicKey,
+        initialVirtualTokenReserves: new anchor.BN(100_000_000_000_000),
+        initialRealTokenReserves: new anchor.BN(50_000_000_000_000),
+        virtualSolReserves: new anchor.BN(30_000_000_000),
+        virtualTokenReserves: new anchor.BN(100_000_000_000_000),
+        realSolReserves: new anchor.BN(0),
+        realTokenReserves: new anchor.BN(50_000_000_000_000),
+        tokenTotalSupply: new anchor.BN(100_000_000_000_000),
+        mintDecimals: 6,
+        startTime: new anchor.BN(startTime.toString()),
+        startSlot: new anchor.BN(startSlot.toString()),
+        complete: false,
+        bump: curveBump,
+        solRaiseTarget: new anchor.BN(1000 * anchor.web3.LAMPORTS_PER_SOL),
+        realmPubkey: anchor.web3.PublicKey.default,
+        migrated: false,
+        feeSchedule: defaultFees,
+        hardCap: false,
+        // Quotes don't touch the vault, but refuse curves without one
+        solVaultBump: 255,
+        endTime: new anchor.BN((startTime + BigInt(7 * DAY)).toString()),
+        refunding: false,
+        marketCapTargetLamports: null,
+        completionReason: null,
+        launchConfig: null,
+      })
+    );
+
+    const mintData = Buffer.alloc(MINT_SIZE);
+    MintLayout.encode(
+      {
+        mintAuthorityOption: 0,
+        mintAuthority: anchor.web3.PublicKey.default,
+        supply: BigInt(100_000_000_000_000),
+        decimals: 6,
+        isInitialized: true,
+        freezeAuthorityOption: 0,
+        freezeAuthority: anchor.web3.PublicKey.default,
+      },
+      mintData
+    );
+    context.setAccount(mint, {
+      lamports: 1_000_000_000,
+      data: mintData,
+      owner: TOKEN_PROGRAM_ID,
+      executable: false,
+    });
+  });
+
+  it("Open trading at the start slot, whatever the timestamp", async () => {
+    // A day past start_time by wall clock, one slot short of the launch
+    await warp(startSlot - BigInt(1), startTime + BigInt(DAY));
+    await expectError(quoteBuy(), "CurveNotStarted");
+
+    // On the launch slot with a clock running a day behind
+    await warp(startSlot, startTime - BigInt(DAY));
+    assert.equal(await buyFeeBps(), 9_900);
+  });
+
+  it("Decay the fee by slots, not by elapsed time", async () => {
+    // Hours of wall clock time don't move the fee while the slot stands still
+    await warp(startSlot + BigInt(149), startTime + BigInt(DAY / 4));
+    assert.equal(await buyFeeBps(), 9_900);
+
+    // And a frozen timestamp doesn't hold it back once the slots have passed
+    await warp(startSlot + BigInt(150), startTime);
+    assert.equal(await buyFeeBps(), 917);
+    await warp(startSlot + BigInt(200), startTime);
+    assert.equal(await buyFeeBps(), 502);
+    await warp(startSlot + BigInt(251), startTime);
+    assert.equal(await buyFeeBps(), 100);
+  });
+
+  it("Close the fundraising window by timestamp", async () => {
+    const endTime = startTime + BigInt(7 * DAY);
+    await warp(startSlot + BigInt(300), endTime - BigInt(1));
+    assert.equal(await buyFeeBps(), 100);
+
+    // The slot barely moved, the deadline still passed
+    await warp(startSlot + BigInt(301), endTime);
+    await expectError(quoteBuy(), "CurveExpired");
+  });
+});